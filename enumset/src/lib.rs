@@ -4,7 +4,12 @@
 //! A library for defining enums that can be used in compact bit sets. It supports enums up to 128
 //! variants, and has a macro to use these sets in constants.
 //!
-//! For serde support, enable the `serde` feature.
+//! For serde support, enable the `serde` feature. For conversions into types from the `alloc`
+//! crate, such as `EnumSet::to_vec`, enable the `alloc` feature. For conversions into an
+//! `ArrayVec`, which does not require an allocator, enable the `arrayvec` feature. For
+//! conversions to and from the `EnumMap` type of the `enum-map` crate, enable the `enum-map`
+//! feature. For conversions to and from the `FixedBitSet` type of the `fixedbitset` crate, enable
+//! the `fixedbitset` feature.
 //!
 //! # Defining enums for use with EnumSet
 //!
@@ -75,13 +80,17 @@
 //! assert_eq!(set, Enum::A | Enum::E | Enum::G);
 //! ```
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
 pub use wasmer_enumset_derive::*;
 
 use core::cmp::Ordering;
+use core::convert::TryFrom;
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use core::hash::{Hash, Hasher};
-use core::iter::FromIterator;
+use core::iter::{FromIterator, Product, Sum};
 use core::ops::*;
 
 use num_traits::*;
@@ -91,7 +100,7 @@ use num_traits::*;
 pub mod __internal {
     use super::*;
 
-    /// A struct used to type check [`enum_set!`].
+    /// A struct used to type check [`enum_set!`] and [`enum_set_contains!`].
     pub struct EnumSetSameTypeHack<'a, T: EnumSetType + 'static> {
         pub unified: &'a [T],
         pub enum_set: EnumSet<T>,
@@ -103,12 +112,161 @@ pub mod __internal {
     /// A reexport of serde so there is no requirement to depend on serde.
     #[cfg(feature = "serde")] pub use serde2 as serde;
 
+    /// The maximum number of bytes in a LEB128 encoded `u128`.
+    #[cfg(feature = "serde")]
+    const VARINT_MAX_LEN: usize = 19;
+
+    /// Serializes a bitset as a LEB128 varint, written as a tuple of bytes.
+    #[cfg(feature = "serde")]
+    pub fn serialize_varint<S: serde::Serializer>(
+        mut bits: u128, ser: S,
+    ) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeTuple;
+        let mut buf = [0u8; VARINT_MAX_LEN];
+        let mut len = 0;
+        loop {
+            let byte = (bits & 0x7F) as u8;
+            bits >>= 7;
+            if bits == 0 {
+                buf[len] = byte;
+                len += 1;
+                break;
+            }
+            buf[len] = byte | 0x80;
+            len += 1;
+        }
+        let mut tuple = ser.serialize_tuple(len)?;
+        for byte in &buf[..len] {
+            tuple.serialize_element(byte)?;
+        }
+        tuple.end()
+    }
+
+    /// Serializes a bitset as an integer of the given width.
+    ///
+    /// The caller must ensure that the bits fit in the width.
+    #[cfg(feature = "serde")]
+    pub fn serialize_with_width<S: serde::Serializer>(
+        bits: u128, width: ReprWidth, ser: S,
+    ) -> Result<S::Ok, S::Error> {
+        use serde::Serialize;
+        match width {
+            ReprWidth::U8 => (bits as u8).serialize(ser),
+            ReprWidth::U16 => (bits as u16).serialize(ser),
+            ReprWidth::U32 => (bits as u32).serialize(ser),
+            ReprWidth::U64 => (bits as u64).serialize(ser),
+            ReprWidth::U128 => bits.serialize(ser),
+            ReprWidth::Varint => serialize_varint(bits, ser),
+        }
+    }
+
+    /// Deserializes a bitset written by [`serialize_with_width`].
+    #[cfg(feature = "serde")]
+    pub fn deserialize_with_width<'de, D: serde::Deserializer<'de>>(
+        width: ReprWidth, de: D,
+    ) -> Result<u128, D::Error> {
+        use serde::Deserialize;
+        Ok(match width {
+            ReprWidth::U8 => u8::deserialize(de)? as u128,
+            ReprWidth::U16 => u16::deserialize(de)? as u128,
+            ReprWidth::U32 => u32::deserialize(de)? as u128,
+            ReprWidth::U64 => u64::deserialize(de)? as u128,
+            ReprWidth::U128 => u128::deserialize(de)?,
+            ReprWidth::Varint => deserialize_varint(de)?,
+        })
+    }
+
+    /// Deserializes a bitset written by [`serialize_varint`].
+    #[cfg(feature = "serde")]
+    pub fn deserialize_varint<'de, D: serde::Deserializer<'de>>(de: D) -> Result<u128, D::Error> {
+        struct Visitor;
+        impl <'de> serde::de::Visitor<'de> for Visitor {
+            type Value = u128;
+            fn expecting(&self, formatter: &mut Formatter) -> fmt::Result {
+                formatter.write_str("a LEB128 encoded bitset")
+            }
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(
+                self, mut seq: A,
+            ) -> Result<Self::Value, A::Error> {
+                use serde::de::Error;
+                let mut value = 0u128;
+                for i in 0..VARINT_MAX_LEN {
+                    let byte = match seq.next_element::<u8>()? {
+                        Some(byte) => byte,
+                        None => return Err(A::Error::invalid_length(i, &self)),
+                    };
+                    let bits = (byte & 0x7F) as u128;
+                    let shift = i as u32 * 7;
+                    if (bits << shift) >> shift != bits {
+                        return Err(A::Error::custom("varint overflows a u128"));
+                    }
+                    value |= bits << shift;
+                    if byte & 0x80 == 0 {
+                        return Ok(value);
+                    }
+                }
+                Err(A::Error::custom("varint overflows a u128"))
+            }
+        }
+        de.deserialize_tuple(VARINT_MAX_LEN, Visitor)
+    }
+
+    /// Checks at compile time that an array length matches the bit width of an enum.
+    pub struct AssertBitWidth<T: EnumSetType, const N: usize>(core::marker::PhantomData<T>);
+    impl <T: EnumSetType, const N: usize> AssertBitWidth<T, N> {
+        pub const OK: () =
+            assert!(N == T::BIT_WIDTH as usize, "Array length must equal bit width.");
+    }
+
+    /// Checks at compile time that an array length matches the size of an enum's bitset.
+    pub struct AssertReprSize<T: EnumSetType, const N: usize>(core::marker::PhantomData<T>);
+    impl <T: EnumSetType, const N: usize> AssertReprSize<T, N> {
+        pub const OK: () = assert!(
+            N == <T::Repr as crate::EnumSetTypeRepr>::WIDTH as usize / 8,
+            "Array length must equal the size of the bitset."
+        );
+    }
+
+    /// Checks at compile time that an array length can hold every variant of an enum.
+    pub struct AssertVariantCapacity<T: EnumSetType, const N: usize>(core::marker::PhantomData<T>);
+    impl <T: EnumSetType, const N: usize> AssertVariantCapacity<T, N> {
+        pub const OK: () =
+            assert!(N >= T::VARIANT_COUNT as usize, "Capacity must fit every variant.");
+    }
+
+    /// Computes the bit a variant is stored in from its discriminant. Used by [`enum_set!`].
+    pub const fn enum_bit<T: EnumSetType>(_: &[T], discriminant: u128) -> u32 {
+        if T::DISCRIMINANT_IS_MASK {
+            discriminant.trailing_zeros()
+        } else {
+            discriminant as u32
+        }
+    }
+
+    /// The actual members of [`EnumSetTypeWithName`]. Implemented by the custom derive for enums
+    /// with the `#[enumset(with_name)]` attribute.
+    pub trait EnumSetTypeWithNamePrivate {
+        /// Returns the name of this variant.
+        fn enum_variant_name(self) -> &'static str;
+    }
+
     /// The actual members of EnumSetType. Put here to avoid polluting global namespaces.
     pub unsafe trait EnumSetTypePrivate {
         /// The underlying type used to store the bitset.
         type Repr: EnumSetTypeRepr;
         /// A mask of bits that are valid in the bitset.
         const ALL_BITS: Self::Repr;
+        /// The number of bits used by the variants of the enum.
+        const BIT_WIDTH: u32;
+        /// The number of variants in the enum.
+        const VARIANT_COUNT: u32;
+        /// Whether the discriminants of the enum are masks rather than bit positions.
+        const DISCRIMINANT_IS_MASK: bool;
+
+        /// An array with one slot per bit of the enum, used as the storage for `EnumSetMap`.
+        type MapArray<V>: AsRef<[Option<V>]> + AsMut<[Option<V>]>;
+        /// Creates a `MapArray` with every slot empty.
+        fn new_map_array<V>() -> Self::MapArray<V>;
 
         /// Converts an enum of this type into its bit position.
         fn enum_into_u32(self) -> u32;
@@ -128,7 +286,8 @@ pub mod __internal {
             where Self: EnumSetType;
     }
 }
-use crate::__internal::EnumSetTypePrivate;
+use crate::__internal::{AssertBitWidth, AssertReprSize, EnumSetTypePrivate};
+use crate::__internal::EnumSetTypeWithNamePrivate;
 #[cfg(feature = "serde")] use crate::__internal::serde;
 #[cfg(feature = "serde")] use crate::serde::{Serialize, Deserialize};
 
@@ -144,6 +303,14 @@ mod private {
     {
         const WIDTH: u32;
 
+        /// A byte array the same size as this type.
+        type Bytes: AsRef<[u8]> + AsMut<[u8]> + Copy + Debug + Eq + Hash;
+
+        fn to_le_bytes(self) -> Self::Bytes;
+        fn to_be_bytes(self) -> Self::Bytes;
+        fn from_le_bytes(bytes: Self::Bytes) -> Self;
+        fn from_be_bytes(bytes: Self::Bytes) -> Self;
+
         fn from_u8(v: u8) -> Self;
         fn from_u16(v: u16) -> Self;
         fn from_u32(v: u32) -> Self;
@@ -155,6 +322,11 @@ mod private {
         ($name:ty, $width:expr) => {
             impl EnumSetTypeRepr for $name {
                 const WIDTH: u32 = $width;
+                type Bytes = [u8; $width / 8];
+                fn to_le_bytes(self) -> Self::Bytes { <$name>::to_le_bytes(self) }
+                fn to_be_bytes(self) -> Self::Bytes { <$name>::to_be_bytes(self) }
+                fn from_le_bytes(bytes: Self::Bytes) -> Self { <$name>::from_le_bytes(bytes) }
+                fn from_be_bytes(bytes: Self::Bytes) -> Self { <$name>::from_be_bytes(bytes) }
                 fn from_u8(v: u8) -> Self { v.as_() }
                 fn from_u16(v: u16) -> Self { v.as_() }
                 fn from_u32(v: u32) -> Self { v.as_() }
@@ -172,6 +344,16 @@ mod private {
 }
 use crate::private::EnumSetTypeRepr;
 
+/// Reinterprets a value as another type of the same size. Used to read the underlying integer
+/// of a set in const functions, where the generic conversions can't be called.
+union ConstCast<A: Copy, B: Copy> {
+    from: A,
+    to: B,
+}
+
+mod map;
+pub use crate::map::{EnumSetMap, EnumSetMapIter};
+
 /// The trait used to define enum types that may be used with [`EnumSet`].
 ///
 /// This trait should be implemented using `#[derive(EnumSetType)]`. Its internal structure is
@@ -187,13 +369,42 @@ use crate::private::EnumSetTypeRepr;
 /// if it were an [`EnumSet`] in expressions. This can be disabled by adding an `#[enumset(no_ops)]`
 /// annotation to the enum.
 ///
+/// These operators treat each variant as a set containing only that variant. In particular,
+/// `!Enum::A` is the complement of that set, containing every variant of the enum except
+/// `Enum::A`.
+///
 /// The custom derive for `EnumSetType` automatically implements [`Copy`], [`Clone`], [`Eq`], and
 /// [`PartialEq`] on the enum. These are required for the [`EnumSet`] to function.
 ///
+/// The `#[enumset(with_name)]` attribute causes the custom derive to generate a
+/// `const fn name(self) -> &'static str` method on the enum returning the name of each variant.
+/// It also implements [`EnumSetTypeWithName`], which enables [`EnumSet::iter_names`].
+///
+/// The `#[enumset(with_mask)]` attribute causes the custom derive to generate a
+/// `const fn mask(self) -> u128` method on the enum returning the mask of the bit each variant is
+/// stored in. This is the same bit [`EnumSet::as_u128`] would set for a set containing only that
+/// variant, so masks can be combined in const contexts and compared against external constants.
+///
+/// By default, the discriminant of each variant is used as the index of the bit it is stored in.
+/// The `#[enumset(discriminant_is_mask)]` attribute instead interprets each discriminant as the
+/// mask of that bit, so that an enum written as `A = 1 << 0, B = 1 << 1` is stored in bits 0 and
+/// 1 rather than bits 1 and 2. Every discriminant must be a power of two in this mode.
+///
+/// Variants removed with `#[cfg(...)]` attributes are ignored, and implicit discriminants are
+/// assigned to the remaining variants as usual, so a variant being configured out changes the bit
+/// that every later variant without an explicit discriminant is stored in. If `EnumSet`s of an
+/// enum with conditionally compiled variants are serialized, give every variant an explicit
+/// discriminant to keep its bit the same across configurations.
+///
 /// In addition, if you have renamed the `enumset` crate in your crate, you can use the
 /// `#[enumset(crate_name = "enumset2")]` attribute to tell the custom derive to use that name
 /// instead.
 ///
+/// By default, the custom derive stores the `EnumSet` in the smallest integer type that can fit
+/// all variants of the enum. You can add a `#[enumset(repr = "u32")]` attribute to force a
+/// particular integer type to be used instead. This can be important for keeping the size of
+/// `#[repr(C)]` structs containing `EnumSet`s predictable.
+///
 /// Attributes controlling the serialization of an `EnumSet` are documented in
 /// [its documentation](./struct.EnumSet.html#serialization).
 ///
@@ -219,6 +430,30 @@ use crate::private::EnumSetTypeRepr;
 /// }
 /// ```
 ///
+/// Deriving an EnumSetType with an explicit storage type:
+///
+/// ```rust
+/// # use enumset::*;
+/// #[derive(EnumSetType)]
+/// #[enumset(repr = "u32")]
+/// pub enum ReprEnum {
+///    A, B, C, D, E,
+/// }
+/// assert_eq!(core::mem::size_of::<EnumSet<ReprEnum>>(), 4);
+/// ```
+///
+/// Deriving an EnumSetType with mask discriminants:
+///
+/// ```rust
+/// # use enumset::*;
+/// #[derive(EnumSetType)]
+/// #[enumset(discriminant_is_mask)]
+/// pub enum MaskEnum {
+///    A = 1 << 0, B = 1 << 1, C = 1 << 2, D = 1 << 3,
+/// }
+/// assert_eq!((MaskEnum::A | MaskEnum::C).as_u8(), 0b101);
+/// ```
+///
 /// Deriving an EnumSetType without adding ops:
 ///
 /// ```rust
@@ -231,6 +466,32 @@ use crate::private::EnumSetTypeRepr;
 /// ```
 pub unsafe trait EnumSetType: Copy + Eq + EnumSetTypePrivate { }
 
+/// An [`EnumSetType`] whose variants know their own names.
+///
+/// This trait is implemented for enums with the `#[enumset(with_name)]` attribute, and cannot be
+/// implemented manually.
+///
+/// # Examples
+///
+/// ```rust
+/// # use enumset::*;
+/// #[derive(EnumSetType, Debug)]
+/// #[enumset(with_name)]
+/// pub enum Enum { A, B, C }
+///
+/// fn describe<T: EnumSetTypeWithName>(value: T) -> &'static str {
+///     value.variant_name()
+/// }
+/// assert_eq!(describe(Enum::B), "B");
+/// ```
+pub trait EnumSetTypeWithName: EnumSetType + EnumSetTypeWithNamePrivate {
+    /// Returns the name of this variant.
+    fn variant_name(self) -> &'static str {
+        self.enum_variant_name()
+    }
+}
+impl <T: EnumSetType + EnumSetTypeWithNamePrivate> EnumSetTypeWithName for T { }
+
 /// An efficient set type for enums.
 ///
 /// It is implemented using a bitset stored using the smallest integer that can fit all bits
@@ -250,6 +511,30 @@ pub unsafe trait EnumSetType: Copy + Eq + EnumSetTypePrivate { }
 /// for serialization. This can be important for avoiding unintentional breaking changes when
 /// `EnumSet`s are serialized with formats like `bincode`.
 ///
+/// If the type given to `serialize_repr` is too small to hold every variant of the enum, the
+/// custom derive fails with a compile-time error, rather than truncating the bitset:
+///
+/// ```compile_fail
+/// # use wasmer_enumset::*;
+/// #[derive(EnumSetType)]
+/// #[enumset(serialize_repr = "u8")]
+/// pub enum Enum {
+///    A, B, C, D, E, F, G, H, I,
+/// }
+/// ```
+///
+/// Alternatively, `#[enumset(serialize_repr = "varint")]` serializes the bitset as a LEB128
+/// varint, written as a tuple of bytes. With compact formats such as `postcard` or `bincode`,
+/// this allows sets containing only low variants to take as little as one byte.
+///
+/// If the width can only be chosen at runtime, [`EnumSet::serialize_as_width`] and
+/// [`EnumSet::deserialize_as_width`] produce the same formats for a width given as a
+/// [`ReprWidth`].
+///
+/// The `#[enumset(serialize_as_bits_struct)]` attribute wraps the integer in a struct with a
+/// single `bits` field, e.g. `{"bits": 5}` in JSON. This matches the format used by the `serde`
+/// support of the `bitflags` crate.
+///
 /// By default, unknown bits are ignored and silently removed from the bitset. To override this
 /// behavior, you can add a `#[enumset(serialize_deny_unknown)]` attribute. This will cause
 /// deserialization to fail if an invalid bit is set.
@@ -257,6 +542,23 @@ pub unsafe trait EnumSetType: Copy + Eq + EnumSetTypePrivate { }
 /// In addition, the `#[enumset(serialize_as_list)]` attribute causes the `EnumSet` to be
 /// instead serialized as a list of enum variants. This requires your enum type implement
 /// [`Serialize`] and [`Deserialize`]. Note that this is a breaking change
+///
+/// Lists are serialized in the same order as [`EnumSet::iter`], which is the order of the bits
+/// the variants are stored in. For enums with explicit discriminants this may differ from the
+/// order the variants are declared in. The `#[enumset(serialize_in_definition_order)]` attribute
+/// instead serializes the variants in declaration order, which keeps human-edited lists stable
+/// and readable. Lists are accepted in any order when deserializing.
+///
+/// When migrating existing data to `serialize_as_list`, the `#[enumset(serialize_accept_legacy)]`
+/// attribute additionally allows the integer format to be deserialized. This requires a
+/// self-describing format such as JSON, as the deserializer must be able to tell which format
+/// it is reading.
+///
+/// The `#[enumset(serialize_human_readable_names)]` attribute serializes the `EnumSet` as a list
+/// of variant names when the format is human readable (such as JSON), and as an integer
+/// otherwise (such as with `bincode`). Unlike `serialize_as_list`, this does not require the enum
+/// type to implement [`Serialize`] or [`Deserialize`]. Unknown names are handled the same way as
+/// unknown bits.
 #[derive(Copy, Clone, PartialEq, Eq)]
 pub struct EnumSet<T: EnumSetType> {
     #[doc(hidden)]
@@ -282,6 +584,10 @@ impl <T: EnumSetType> EnumSet<T> {
     fn all_bits() -> T::Repr {
         T::ALL_BITS
     }
+    // Returns whether a bit corresponds to a variant of the enum
+    fn is_valid_bit(bit: u32) -> bool {
+        bit < T::Repr::WIDTH && Self::all().has_bit(bit)
+    }
 
     /// Creates an empty `EnumSet`.
     pub fn new() -> Self {
@@ -305,35 +611,407 @@ impl <T: EnumSetType> EnumSet<T> {
         EnumSet { __enumset_underlying: Self::all_bits() }
     }
 
+    /// Returns an `EnumSet` containing every variant of the enum for which the predicate
+    /// returns `true`.
+    ///
+    /// The predicate is called once for each valid variant, in the same order as
+    /// [`EnumSet::iter`].
+    pub fn from_predicate<F: FnMut(T) -> bool>(mut f: F) -> Self {
+        Self::all().iter().filter(|v| f(*v)).collect()
+    }
+
+    /// Returns an `EnumSet` containing each variant paired with `true` in an iterator.
+    ///
+    /// Variants paired with `false` are skipped, and do not remove an earlier `true` for the same
+    /// variant.
+    pub fn from_bools<I: IntoIterator<Item = (T, bool)>>(iter: I) -> Self {
+        iter.into_iter().filter(|&(_, present)| present).map(|(v, _)| v).collect()
+    }
+
+    /// Returns an `EnumSet` containing all valid variants within a range.
+    ///
+    /// Variants are ordered by the bit they are stored in, which is their discriminant. An
+    /// unbounded start or end includes all variants before or after the other bound.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C, D, E, F, G }
+    /// assert_eq!(EnumSet::from_range(Enum::B..Enum::E), Enum::B | Enum::C | Enum::D);
+    /// assert_eq!(EnumSet::from_range(Enum::E..), Enum::E | Enum::F | Enum::G);
+    /// assert_eq!(EnumSet::from_range(..=Enum::B), Enum::A | Enum::B);
+    /// ```
+    pub fn from_range<R: RangeBounds<T>>(range: R) -> Self {
+        let start = match range.start_bound() {
+            Bound::Included(t) => t.enum_into_u32(),
+            Bound::Excluded(t) => t.enum_into_u32() + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(t) => t.enum_into_u32() + 1,
+            Bound::Excluded(t) => t.enum_into_u32(),
+            Bound::Unbounded => T::Repr::WIDTH,
+        };
+        if start >= end {
+            Self::new()
+        } else {
+            let bits = Self::partial_bits(end) & !Self::partial_bits(start);
+            EnumSet { __enumset_underlying: bits & Self::all_bits() }
+        }
+    }
+
+    /// Returns an `EnumSet` containing all valid variants with an index below `n`.
+    ///
+    /// If `n` is larger than the width of the bitset, this returns all variants.
+    pub fn mask_below(n: u32) -> Self {
+        EnumSet { __enumset_underlying: Self::partial_bits(n) & Self::all_bits() }
+    }
+
+    /// Returns an `EnumSet` containing all valid variants with an index of `n` or above.
+    ///
+    /// This is the complement of [`EnumSet::mask_below`].
+    pub fn mask_from(n: u32) -> Self {
+        EnumSet { __enumset_underlying: !Self::partial_bits(n) & Self::all_bits() }
+    }
+
+    /// Creates an iterator over every possible `EnumSet` of this type.
+    ///
+    /// Only combinations of valid variants are yielded, even for enums with "sparse" variants.
+    /// Sets are yielded in increasing order of their underlying representation, starting with the
+    /// empty set and ending with [`EnumSet::all`].
+    ///
+    /// Note that this yields `2^n` sets for an enum with `n` variants, so this is only practical
+    /// for enums with few variants, e.g. for exhaustively testing functions taking an `EnumSet`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B }
+    /// let sets: Vec<_> = EnumSet::<Enum>::power_set().collect();
+    /// assert_eq!(sets, [EnumSet::new(), Enum::A.into(), Enum::B.into(), Enum::A | Enum::B]);
+    /// ```
+    pub fn power_set() -> EnumSetPowerSet<T> {
+        EnumSetPowerSet(Some(T::Repr::zero()))
+    }
+
+    /// Returns the index of this set among all sets of this type, with the variants of the enum
+    /// packed into consecutive bits.
+    ///
+    /// For enums with "sparse" variants, this removes the gaps between the bits of the
+    /// underlying representation, so that the result is always less than
+    /// [`EnumSet::SUBSET_COUNT`]. This is suitable for indexing into tables with one entry per
+    /// set. For enums without gaps, this is the same as [`EnumSet::as_u128`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A = 1, B = 5, C = 10 }
+    /// assert_eq!((Enum::A | Enum::C).dense_index(), 0b101);
+    /// assert_eq!(EnumSet::<Enum>::from_dense_index(0b101), Enum::A | Enum::C);
+    /// ```
+    pub fn dense_index(&self) -> u128 {
+        let mut index = 0;
+        for (i, (bit, _)) in Self::all().iter_indexed().enumerate() {
+            if self.has_bit(bit) {
+                index |= 1 << i;
+            }
+        }
+        index
+    }
+
+    /// Constructs a bitset from an index returned by [`EnumSet::dense_index`].
+    ///
+    /// If the index is not less than [`EnumSet::SUBSET_COUNT`], this method will panic.
+    pub fn from_dense_index(index: u128) -> Self {
+        let count = Self::variant_count();
+        assert!(count >= 128 || index >> count == 0, "Dense index is out of range.");
+        let mut set = Self::new();
+        for (i, (bit, _)) in Self::all().iter_indexed().enumerate() {
+            if index & (1 << i) != 0 {
+                set.__enumset_underlying = set.__enumset_underlying | Self::mask(bit);
+            }
+        }
+        set
+    }
+
     /// Total number of bits used by this type. Note that the actual amount of space used is
     /// rounded up to the next highest integer type (`u8`, `u16`, `u32`, `u64`, or `u128`).
     ///
     /// This is the same as [`EnumSet::variant_count`] except in enums with "sparse" variants.
     /// (e.g. `enum Foo { A = 10, B = 20 }`)
-    pub fn bit_width() -> u32 {
-        T::Repr::WIDTH - T::ALL_BITS.leading_zeros()
+    ///
+    /// This can be evaluated in const contexts.
+    pub const fn bit_width() -> u32 {
+        T::BIT_WIDTH
     }
 
     /// The number of valid variants that this type can contain.
     ///
     /// This is the same as [`EnumSet::bit_width`] except in enums with "sparse" variants.
     /// (e.g. `enum Foo { A = 10, B = 20 }`)
-    pub fn variant_count() -> u32 {
-        T::ALL_BITS.count_ones()
+    ///
+    /// This can be evaluated in const contexts, e.g. to size an array with one element per
+    /// variant:
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C = 10 }
+    /// let names = [""; EnumSet::<Enum>::variant_count() as usize];
+    /// assert_eq!(names.len(), 3);
+    /// ```
+    pub const fn variant_count() -> u32 {
+        T::VARIANT_COUNT
+    }
+
+    /// Returns the underlying bits of this set widened to a `u128`. Unlike [`EnumSet::as_u128`],
+    /// this can be evaluated in const contexts.
+    const fn const_bits(self) -> u128 {
+        let from = self.__enumset_underlying;
+        // `T::Repr` is always one of `u8`, `u16`, `u32`, `u64` or `u128`, so each arm reads it
+        // as the unsigned integer type of the same size, which is the type itself.
+        unsafe {
+            match core::mem::size_of::<T::Repr>() {
+                1 => ConstCast::<T::Repr, u8> { from }.to as u128,
+                2 => ConstCast::<T::Repr, u16> { from }.to as u128,
+                4 => ConstCast::<T::Repr, u32> { from }.to as u128,
+                8 => ConstCast::<T::Repr, u64> { from }.to as u128,
+                _ => ConstCast::<T::Repr, u128> { from }.to,
+            }
+        }
+    }
+
+    /// Checks whether this set contains the same values as another set.
+    ///
+    /// This is the same as comparing the sets with `==`, but can be evaluated in const contexts.
+    /// The [`enum_set_eq!`] macro can be used for the same purpose.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C }
+    /// const SET: EnumSet<Enum> = enum_set!(Enum::A | Enum::C);
+    /// const _: () = assert!(SET.const_eq(enum_set!(Enum::C | Enum::A)));
+    /// const _: () = assert!(!SET.const_eq(enum_set!(Enum::A)));
+    /// ```
+    pub const fn const_eq(&self, other: Self) -> bool {
+        (*self).const_bits() == other.const_bits()
+    }
+
+    /// Returns the index of the bit a value is stored in.
+    ///
+    /// This is the discriminant of the variant, and is suitable for indexing into arrays with
+    /// one element per bit of the set.
+    pub fn index_of(value: T) -> u32 {
+        value.enum_into_u32()
+    }
+
+    /// Returns the value stored in a given bit, or `None` if the bit does not correspond to a
+    /// variant of the enum.
+    pub fn variant_at(index: u32) -> Option<T> {
+        if Self::is_valid_bit(index) {
+            Some(unsafe { T::enum_from_u32(index) })
+        } else {
+            None
+        }
+    }
+
+    /// The number of bytes used by the underlying representation of this type.
+    ///
+    /// This is the size of the integer type returned by [`EnumSet::as_repr`].
+    pub const REPR_SIZE_BYTES: usize = T::Repr::WIDTH as usize / 8;
+
+    /// Whether all variants of this type fit in a `u8` bitset.
+    ///
+    /// This can be used to check at compile time that an enum has not outgrown a serialized
+    /// format, e.g. `const _: () = assert!(EnumSet::<Enum>::FITS_IN_U8);`
+    pub const FITS_IN_U8: bool = T::BIT_WIDTH <= 8;
+    /// Whether all variants of this type fit in a `u16` bitset.
+    pub const FITS_IN_U16: bool = T::BIT_WIDTH <= 16;
+    /// Whether all variants of this type fit in a `u32` bitset.
+    pub const FITS_IN_U32: bool = T::BIT_WIDTH <= 32;
+    /// Whether all variants of this type fit in a `u64` bitset.
+    pub const FITS_IN_U64: bool = T::BIT_WIDTH <= 64;
+
+    /// The number of distinct sets of this type, which is `2^n` for an enum with `n` variants.
+    ///
+    /// This is the number of sets yielded by [`EnumSet::power_set`], and can be used to size
+    /// tables with one entry per set. This saturates to `u128::MAX` for an enum with 128 variants.
+    pub const SUBSET_COUNT: u128 = if T::VARIANT_COUNT >= 128 {
+        u128::MAX
+    } else {
+        1 << T::VARIANT_COUNT
+    };
+
+    /// Returns the number of bytes used by the underlying representation of this type.
+    ///
+    /// This is the same as [`EnumSet::REPR_SIZE_BYTES`].
+    pub fn repr_size_bytes() -> usize {
+        Self::REPR_SIZE_BYTES
+    }
+
+    /// Returns the underlying integer representation of this set.
+    ///
+    /// The type returned is the smallest integer type that can fit all variants of the enum,
+    /// unless it has been overridden with an `#[enumset(repr = "...")]` attribute.
+    pub fn as_repr(&self) -> T::Repr {
+        self.__enumset_underlying
+    }
+
+    /// Returns the elements of this set widened to a `u128`, suitable for hashing.
+    ///
+    /// Unlike the [`Hash`] implementation of `EnumSet`, which hashes the underlying integer
+    /// representation directly, the value returned by this method depends only on which variants
+    /// are in the set. It does not change when the enum grows enough that its representation is
+    /// widened (e.g. from a `u64` to a `u128`), or when it is compiled with a different
+    /// `#[enumset(repr = "...")]` attribute, as long as the existing variants keep their
+    /// discriminants.
+    ///
+    /// Only this value is stable. When hashes are persisted, it should be fed to a hasher with a
+    /// fixed, specified algorithm, not `std`'s `DefaultHasher`, whose output may change between
+    /// Rust releases.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C }
+    /// # #[derive(EnumSetType, Debug)] #[enumset(repr = "u64")] pub enum WideEnum { A, B, C }
+    /// let narrow = (Enum::A | Enum::C).canonical_hash_bits();
+    /// let wide = (WideEnum::A | WideEnum::C).canonical_hash_bits();
+    /// assert_eq!(narrow, wide);
+    /// ```
+    pub fn canonical_hash_bits(&self) -> u128 {
+        self.__enumset_underlying.as_()
+    }
+
+    /// Constructs a bitset from its underlying integer representation.
+    ///
+    /// If a bit that doesn't correspond to an enum variant is set, this method will panic.
+    pub fn from_repr(bits: T::Repr) -> Self {
+        Self::try_from_repr(bits).expect("Bitset contains invalid variants.")
+    }
+
+    /// Attempts to constructs a bitset from its underlying integer representation.
+    ///
+    /// If a bit that doesn't correspond to an enum variant is set, this method will return
+    /// `None`.
+    pub fn try_from_repr(bits: T::Repr) -> Option<Self> {
+        if Self::repr_is_valid(bits) {
+            Some(EnumSet { __enumset_underlying: bits })
+        } else {
+            None
+        }
+    }
+
+    /// Checks whether an underlying integer representation only contains bits that correspond
+    /// to variants of the enum.
+    pub fn repr_is_valid(bits: T::Repr) -> bool {
+        (bits & !Self::all_bits()).is_zero()
+    }
+
+    /// Constructs a bitset from its underlying integer representation, ignoring invalid
+    /// variants.
+    pub fn from_repr_truncated(bits: T::Repr) -> Self {
+        EnumSet { __enumset_underlying: bits & Self::all_bits() }
+    }
+
+    /// Constructs a bitset from its underlying integer representation.
+    ///
+    /// If a bit that doesn't correspond to an enum variant is set, this method will return an
+    /// error reporting the invalid bits. This is the same check performed when deserializing
+    /// an enum with the `#[enumset(serialize_deny_unknown)]` attribute.
+    pub fn from_repr_deny_unknown(bits: T::Repr) -> Result<Self, UnknownBitsError> {
+        let unknown = bits & !Self::all_bits();
+        if unknown.is_zero() {
+            Ok(EnumSet { __enumset_underlying: bits })
+        } else {
+            Err(UnknownBitsError { unknown_bits: unknown.as_() })
+        }
+    }
+
+    /// Constructs a bitset from a `u128` containing its bits.
+    ///
+    /// If a bit that doesn't correspond to an enum variant is set, this method will return a mask
+    /// of the invalid bits. As the input is always a `u128`, bits widened from an integer of any
+    /// width can be passed in, such as the `bits()` of a `bitflags` value.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C }
+    /// assert_eq!(EnumSet::<Enum>::from_raw_bits_checked(0b101), Ok(Enum::A | Enum::C));
+    /// assert_eq!(EnumSet::<Enum>::from_raw_bits_checked(0b1001), Err(0b1000));
+    /// ```
+    pub fn from_raw_bits_checked(bits: u128) -> Result<Self, u128> {
+        Self::try_from(bits).map_err(|error| error.unknown_bits())
     }
 
     /// Returns the number of elements in this set.
     pub fn len(&self) -> usize {
         self.__enumset_underlying.count_ones() as usize
     }
+    /// Returns the number of elements in this set as a `u32`.
+    ///
+    /// This is the same as [`EnumSet::len`], but avoids casts when used alongside bit indices
+    /// and widths, which are `u32`.
+    pub fn count(&self) -> u32 {
+        self.__enumset_underlying.count_ones()
+    }
+    /// Returns the number of bits above the highest value in this set, up to
+    /// [`EnumSet::bit_width`].
+    ///
+    /// This is the number of leading zeros of the underlying representation, counted within the
+    /// bits used by the enum. An empty set returns [`EnumSet::bit_width`].
+    pub fn leading_absent(&self) -> u32 {
+        let unused = T::Repr::WIDTH - T::BIT_WIDTH;
+        self.__enumset_underlying.leading_zeros().saturating_sub(unused)
+    }
+    /// Returns the number of bits below the lowest value in this set.
+    ///
+    /// This is the number of trailing zeros of the underlying representation. An empty set
+    /// returns [`EnumSet::bit_width`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C, D, E, F, G }
+    /// let set = Enum::B | Enum::D;
+    /// assert_eq!(set.trailing_absent(), 1);
+    /// assert_eq!(set.leading_absent(), 3);
+    /// assert_eq!(EnumSet::<Enum>::empty().leading_absent(), 7);
+    /// ```
+    pub fn trailing_absent(&self) -> u32 {
+        self.__enumset_underlying.trailing_zeros().min(T::BIT_WIDTH)
+    }
     /// Returns `true` if the set contains no elements.
     pub fn is_empty(&self) -> bool {
         self.__enumset_underlying.is_zero()
     }
+    /// Returns `true` if the set contains all valid variants of the enum.
+    pub fn is_all(&self) -> bool {
+        self.__enumset_underlying == Self::all_bits()
+    }
+    /// Returns `true` if the set contains exactly one element.
+    ///
+    /// This is the same as `self.len() == 1`, but avoids counting the bits in the set.
+    pub fn is_singleton(&self) -> bool {
+        let bits = self.__enumset_underlying;
+        !bits.is_zero() && (bits & (bits - T::Repr::one())).is_zero()
+    }
     /// Removes all elements from the set.
     pub fn clear(&mut self) {
         self.__enumset_underlying = T::Repr::zero()
     }
+    /// Adds all valid variants of the enum to the set.
+    pub fn fill(&mut self) {
+        self.__enumset_underlying = Self::all_bits()
+    }
 
     /// Returns `true` if `self` has no elements in common with `other`. This is equivalent to
     /// checking for an empty intersection.
@@ -368,16 +1046,253 @@ impl <T: EnumSetType> EnumSet<T> {
     pub fn symmetrical_difference(&self, other: Self) -> Self {
         EnumSet { __enumset_underlying: self.__enumset_underlying ^ other.__enumset_underlying }
     }
+    /// Returns the symmetric difference of `self` and `other`, split by which set each element
+    /// came from.
+    ///
+    /// The first set contains the elements only present in `self`, and the second set contains
+    /// the elements only present in `other`.
+    pub fn difference_report(&self, other: Self) -> (Self, Self) {
+        let diff = self.__enumset_underlying ^ other.__enumset_underlying;
+        (
+            EnumSet { __enumset_underlying: diff & self.__enumset_underlying },
+            EnumSet { __enumset_underlying: diff & other.__enumset_underlying },
+        )
+    }
+    /// Returns the union of this set and `other`, resolving conflicts in favor of this set.
+    ///
+    /// Each pair in `conflicts` lists two values that should not both be present. If this set
+    /// contains one value of a pair but not the other, the other value is not taken from `other`.
+    /// Pairs where this set contains both or neither value are merged as a plain union. This can
+    /// be used to layer configurations, where a later layer overrides an earlier one within
+    /// groups of mutually exclusive flags.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { Fast, Small, Verbose, Quiet, Color }
+    /// let conflicts = [(Enum::Fast, Enum::Small), (Enum::Verbose, Enum::Quiet)];
+    /// let base = Enum::Small | Enum::Quiet | Enum::Color;
+    /// let layer = Enum::Fast | Enum::Color;
+    /// assert_eq!(layer.merge_preferring_self(base, &conflicts),
+    ///            Enum::Fast | Enum::Quiet | Enum::Color);
+    /// ```
+    pub fn merge_preferring_self(&self, other: Self, conflicts: &[(T, T)]) -> Self {
+        let mut merged = self.union(other);
+        for &(a, b) in conflicts {
+            if self.contains(a) && !self.contains(b) {
+                merged.remove(b);
+            } else if self.contains(b) && !self.contains(a) {
+                merged.remove(a);
+            }
+        }
+        merged
+    }
+    /// Returns the values in `candidates` that are present in this set.
+    ///
+    /// This is the same as [`EnumSet::intersection`], but reads better when checking which of a
+    /// set of required flags are held, e.g. `granted.present_among(required)`.
+    pub fn present_among(&self, candidates: Self) -> Self {
+        self.intersection(candidates)
+    }
+    /// Returns the values in `candidates` that are not present in this set.
+    ///
+    /// This is the same as `candidates - self`, e.g. `granted.absent_among(required)` returns
+    /// the required flags that are missing.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C, D, E, F, G }
+    /// let granted = Enum::A | Enum::B | Enum::E;
+    /// let required = Enum::A | Enum::C | Enum::E;
+    /// assert_eq!(granted.present_among(required), Enum::A | Enum::E);
+    /// assert_eq!(granted.absent_among(required), Enum::C);
+    /// ```
+    pub fn absent_among(&self, candidates: Self) -> Self {
+        candidates.difference(*self)
+    }
+    /// Returns the value with the highest discriminant that is present in exactly one of the two
+    /// sets, or `None` if the sets are equal.
+    pub fn highest_diff(&self, other: Self) -> Option<T> {
+        Self::highest_in(self.__enumset_underlying ^ other.__enumset_underlying)
+    }
+    /// Returns the number of elements present in both sets, without constructing the
+    /// intersection.
+    pub fn intersection_len(&self, other: Self) -> usize {
+        (self.__enumset_underlying & other.__enumset_underlying).count_ones() as usize
+    }
+    /// Returns the number of elements present in either set, without constructing the union.
+    pub fn union_len(&self, other: Self) -> usize {
+        (self.__enumset_underlying | other.__enumset_underlying).count_ones() as usize
+    }
+    /// Returns the number of elements present in `self` but not in `other`, without
+    /// constructing the difference.
+    pub fn difference_len(&self, other: Self) -> usize {
+        (self.__enumset_underlying & !other.__enumset_underlying).count_ones() as usize
+    }
+    /// Returns `true` if both sets contain the same variants, ignoring any bits that do not
+    /// correspond to a variant of the enum.
+    ///
+    /// Sets constructed through the safe API never contain such bits, so this only differs
+    /// from `==` for sets built directly from raw bits, e.g. over FFI.
+    pub fn eq_valid(&self, other: Self) -> bool {
+        ((self.__enumset_underlying ^ other.__enumset_underlying) & Self::all_bits()).is_zero()
+    }
+    /// Returns the Jaccard similarity of two sets, i.e. the size of their intersection divided
+    /// by the size of their union.
+    ///
+    /// If both sets are empty, this returns `0.0`.
+    pub fn jaccard_similarity(&self, other: Self) -> f64 {
+        let union_len = self.union_len(other);
+        if union_len == 0 {
+            0.0
+        } else {
+            self.intersection_len(other) as f64 / union_len as f64
+        }
+    }
+    /// Returns the overlap coefficient of two sets, i.e. the size of their intersection divided
+    /// by the size of the smaller set.
+    ///
+    /// If either set is empty, this returns `0.0`.
+    pub fn overlap_coefficient(&self, other: Self) -> f64 {
+        let min_len = self.len().min(other.len());
+        if min_len == 0 {
+            0.0
+        } else {
+            self.intersection_len(other) as f64 / min_len as f64
+        }
+    }
     /// Returns a set containing all enum variants not in this set.
     pub fn complement(&self) -> Self {
         EnumSet { __enumset_underlying: !self.__enumset_underlying & Self::all_bits() }
     }
+    /// Returns a set containing all variants in `universe` that are not in this set.
+    ///
+    /// This is the complement of this set relative to `universe`, and is equivalent to
+    /// `universe - self`.
+    pub fn complement_within(&self, universe: Self) -> Self {
+        universe.difference(*self)
+    }
+
+    /// Returns the only valid variant not in this set, or `None` if the number of variants
+    /// missing from this set is not exactly one.
+    pub fn single_missing(&self) -> Option<T> {
+        let missing = self.complement();
+        if missing.len() == 1 {
+            unsafe { Some(T::enum_from_u32(missing.__enumset_underlying.trailing_zeros())) }
+        } else {
+            None
+        }
+    }
+
+    /// Returns the valid variant with the lowest discriminant that is not in this set, or `None`
+    /// if the set contains every variant.
+    ///
+    /// When a set is used to track which slots of a small fixed universe are in use, this
+    /// returns the first free slot.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C, D, E, F, G }
+    /// let mut used = Enum::A | Enum::B | Enum::D;
+    /// assert_eq!(used.first_absent(), Some(Enum::C));
+    /// used.fill();
+    /// assert_eq!(used.first_absent(), None);
+    /// ```
+    pub fn first_absent(&self) -> Option<T> {
+        Self::lowest_in(!self.__enumset_underlying & Self::all_bits())
+    }
+
+    /// Compares the elements of two sets lexicographically, in the order they are defined in
+    /// the enum.
+    ///
+    /// Unlike the [`Ord`] implementation of `EnumSet`, which compares the underlying integers,
+    /// this orders sets the same way as comparing their sorted lists of elements would.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # use core::cmp::Ordering;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C, D, E, F, G }
+    /// assert_eq!((Enum::A | Enum::C).cmp_by_elements(&(Enum::B.into())), Ordering::Less);
+    /// assert_eq!(EnumSet::only(Enum::A).cmp_by_elements(&(Enum::A | Enum::B)), Ordering::Less);
+    /// ```
+    pub fn cmp_by_elements(&self, other: &Self) -> Ordering {
+        let self_bits = self.iter().map(|v| v.enum_into_u32());
+        let other_bits = other.iter().map(|v| v.enum_into_u32());
+        self_bits.cmp(other_bits)
+    }
 
     /// Checks whether this set contains a value.
     pub fn contains(&self, value: T) -> bool {
         self.has_bit(value.enum_into_u32())
     }
 
+    // Returns the value stored in the lowest bit set in `bits`.
+    fn lowest_in(bits: T::Repr) -> Option<T> {
+        if bits.is_zero() {
+            None
+        } else {
+            unsafe { Some(T::enum_from_u32(bits.trailing_zeros())) }
+        }
+    }
+    // Returns the value stored in the highest bit set in `bits`.
+    fn highest_in(bits: T::Repr) -> Option<T> {
+        if bits.is_zero() {
+            None
+        } else {
+            unsafe { Some(T::enum_from_u32(T::Repr::WIDTH - 1 - bits.leading_zeros())) }
+        }
+    }
+
+    /// Returns the value in this set with the lowest discriminant greater than that of `after`.
+    pub fn next_present(&self, after: T) -> Option<T> {
+        Self::lowest_in(self.__enumset_underlying & !Self::partial_bits(after.enum_into_u32() + 1))
+    }
+    /// Returns the value in this set with the highest discriminant less than that of `before`.
+    pub fn prev_present(&self, before: T) -> Option<T> {
+        Self::highest_in(self.__enumset_underlying & Self::partial_bits(before.enum_into_u32()))
+    }
+    /// Returns the value in this set following `after`, wrapping around to the first value in
+    /// this set if there is none.
+    ///
+    /// This returns `after` itself if it is the only value in this set, and `None` if this set is
+    /// empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C, D }
+    /// let tabs = Enum::A | Enum::C;
+    /// assert_eq!(tabs.next_present_wrapping(Enum::A), Some(Enum::C));
+    /// assert_eq!(tabs.next_present_wrapping(Enum::C), Some(Enum::A));
+    /// assert_eq!(tabs.prev_present_wrapping(Enum::A), Some(Enum::C));
+    /// ```
+    pub fn next_present_wrapping(&self, after: T) -> Option<T> {
+        self.next_present(after).or_else(|| Self::lowest_in(self.__enumset_underlying))
+    }
+    /// Returns the value in this set preceding `before`, wrapping around to the last value in
+    /// this set if there is none.
+    ///
+    /// This returns `before` itself if it is the only value in this set, and `None` if this set
+    /// is empty.
+    pub fn prev_present_wrapping(&self, before: T) -> Option<T> {
+        self.prev_present(before).or_else(|| Self::highest_in(self.__enumset_underlying))
+    }
+
+    /// Checks whether this set contains the value stored in a given bit.
+    ///
+    /// Indices past the end of the underlying representation return `false`.
+    pub fn contains_index(&self, index: u32) -> bool {
+        index < T::Repr::WIDTH && self.has_bit(index)
+    }
+
     /// Adds a value to this set.
     ///
     /// If the set did not have this value present, `true` is returned.
@@ -399,18 +1314,806 @@ impl <T: EnumSetType> EnumSet<T> {
     pub fn insert_all(&mut self, other: Self) {
         self.__enumset_underlying = self.__enumset_underlying | other.__enumset_underlying
     }
+    /// Returns this set with a value added.
+    ///
+    /// This allows sets to be built fluently, e.g. `EnumSet::new().with(Enum::A).with(Enum::B)`.
+    pub fn with(mut self, value: T) -> Self {
+        self.insert(value);
+        self
+    }
+    /// Returns this set with a value removed.
+    pub fn without(mut self, value: T) -> Self {
+        self.remove(value);
+        self
+    }
+
+    /// Adds all values from an iterator to this set, returning the number of values that were
+    /// not already present.
+    ///
+    /// Values repeated in the iterator are only counted the first time they are added.
+    pub fn insert_iter<I: IntoIterator<Item = T>>(&mut self, iter: I) -> usize {
+        iter.into_iter().filter(|&value| self.insert(value)).count()
+    }
     /// Removes all values in another set from this one.
     pub fn remove_all(&mut self, other: Self) {
         self.__enumset_underlying = self.__enumset_underlying & !other.__enumset_underlying
     }
-
-    /// Creates an iterator over the values in this set.
+    /// Removes all values in `clear` from this set, then adds all values in `set` to it.
     ///
-    /// Note that iterator invalidation is impossible as the iterator contains a copy of this type,
-    /// rather than holding a reference to it.
-    pub fn iter(&self) -> EnumSetIter<T> {
+    /// This computes `(self - clear) | set`. Values present in both masks end up in the set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C, D, E, F, G }
+    /// let mut set = Enum::A | Enum::B | Enum::C;
+    /// set.apply(Enum::B | Enum::C, Enum::C | Enum::D);
+    /// assert_eq!(set, Enum::A | Enum::C | Enum::D);
+    /// ```
+    pub fn apply(&mut self, clear: Self, set: Self) {
+        self.__enumset_underlying =
+            (self.__enumset_underlying & !clear.__enumset_underlying) | set.__enumset_underlying
+    }
+
+    /// Adds all elements in another set to this one, returning whether this set changed.
+    pub fn insert_all_changed(&mut self, other: Self) -> bool {
+        let old = self.__enumset_underlying;
+        self.insert_all(other);
+        old != self.__enumset_underlying
+    }
+    /// Removes all values in another set from this one, returning whether this set changed.
+    pub fn remove_all_changed(&mut self, other: Self) -> bool {
+        let old = self.__enumset_underlying;
+        self.remove_all(other);
+        old != self.__enumset_underlying
+    }
+
+    /// Removes all values not in `allowed` from this set.
+    ///
+    /// This is equivalent to `*self &= allowed`.
+    pub fn clamp_to(&mut self, allowed: Self) {
+        self.__enumset_underlying = self.__enumset_underlying & allowed.__enumset_underlying
+    }
+    /// Returns a copy of this set with all values not in `allowed` removed.
+    ///
+    /// This is equivalent to `self & allowed`.
+    pub fn clamped(&self, allowed: Self) -> Self {
+        self.intersection(allowed)
+    }
+
+    /// Removes all but the `n` values with the lowest discriminants from this set.
+    ///
+    /// This has no effect if the set contains `n` or fewer values.
+    pub fn truncate(&mut self, n: usize) {
+        let mut dropped = self.__enumset_underlying;
+        for _ in 0..n {
+            if dropped.is_zero() {
+                return;
+            }
+            dropped = dropped & !Self::mask(dropped.trailing_zeros());
+        }
+        self.__enumset_underlying = self.__enumset_underlying & !dropped;
+    }
+
+    /// Splits this set in two at the given value.
+    ///
+    /// All values with a discriminant greater than or equal to `at` are removed from this set
+    /// and returned as a new set.
+    pub fn split_off(&mut self, at: T) -> Self {
+        let mask = !Self::partial_bits(at.enum_into_u32());
+        let split = EnumSet { __enumset_underlying: self.__enumset_underlying & mask };
+        self.__enumset_underlying = self.__enumset_underlying & !mask;
+        split
+    }
+
+    // Shifts the bits at or above `pivot` by `by` positions, returning the shifted bits
+    // alongside whether any bits were moved out of range, into an invalid position, or onto a
+    // bit below `pivot` that was already set.
+    fn shift_above_bits(&self, pivot: u32, by: i32) -> (u128, bool) {
+        let bits: u128 = self.__enumset_underlying.as_();
+        let low_mask = 1u128.checked_shl(pivot).unwrap_or(0).wrapping_sub(1);
+        let high = bits & !low_mask;
+        let distance = by.unsigned_abs();
+        let (moved, lost) = if by >= 0 {
+            let moved = high.checked_shl(distance).unwrap_or(0);
+            (moved, moved.checked_shr(distance).unwrap_or(0) != high)
+        } else {
+            let moved = high.checked_shr(distance).unwrap_or(0);
+            (moved, moved.checked_shl(distance).unwrap_or(0) != high)
+        };
+        let all_bits: u128 = Self::all_bits().as_();
+        let collided = moved & bits & low_mask != 0;
+        let shifted = (bits & low_mask) | moved;
+        (shifted & all_bits, lost || collided || shifted & !all_bits != 0)
+    }
+
+    /// Returns a set with every value stored at or above the bit `pivot` moved by `by` bits,
+    /// which may be negative. Values stored below `pivot` are left in place.
+    ///
+    /// This is intended for migrating sets stored using an older version of an enum, such as
+    /// after a variant was inserted in the middle of it.
+    ///
+    /// If a value would be moved to a bit that doesn't correspond to a variant, or onto a value
+    /// stored below `pivot` by a negative shift, this method will panic.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C, D, E, F, G }
+    /// let set = Enum::A | Enum::B | Enum::D;
+    /// assert_eq!(set.shift_above(1, 2), Enum::A | Enum::D | Enum::F);
+    /// assert_eq!(set.shift_above(3, -1), Enum::A | Enum::B | Enum::C);
+    /// ```
+    pub fn shift_above(&self, pivot: u32, by: i32) -> Self {
+        self.try_shift_above(pivot, by)
+            .expect("Shifted bitset contains invalid or overlapping variants.")
+    }
+
+    /// Returns a set with every value stored at or above the bit `pivot` moved by `by` bits,
+    /// which may be negative.
+    ///
+    /// If a value would be moved to a bit that doesn't correspond to a variant, or onto a value
+    /// stored below `pivot` by a negative shift, this method will instead return `None`.
+    pub fn try_shift_above(&self, pivot: u32, by: i32) -> Option<Self> {
+        match self.shift_above_bits(pivot, by) {
+            (bits, false) => {
+                let bits = <T::Repr as EnumSetTypeRepr>::from_u128(bits);
+                Some(EnumSet { __enumset_underlying: bits })
+            }
+            (_, true) => None,
+        }
+    }
+
+    /// Returns a set with every value stored at or above the bit `pivot` moved by `by` bits,
+    /// which may be negative.
+    ///
+    /// Any value that would be moved to a bit that doesn't correspond to a variant is dropped, and
+    /// a value moved onto a value stored below `pivot` is merged with it.
+    pub fn shift_above_truncated(&self, pivot: u32, by: i32) -> Self {
+        let (bits, _) = self.shift_above_bits(pivot, by);
+        EnumSet { __enumset_underlying: <T::Repr as EnumSetTypeRepr>::from_u128(bits) }
+    }
+
+    /// Returns a set with the order of the bits reversed within [`EnumSet::bit_width`], so that
+    /// the value in bit `0` moves to bit `bit_width() - 1` and vice versa.
+    ///
+    /// Any bits moved to positions that don't correspond to a variant are dropped. This is
+    /// intended for converting to and from encodings that store bits in the opposite order.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C, D, E, F, G }
+    /// assert_eq!((Enum::A | Enum::C).reverse_bits_within_width(), Enum::G | Enum::E);
+    /// ```
+    pub fn reverse_bits_within_width(&self) -> Self {
+        let width = Self::bit_width();
+        let mut bits = self.__enumset_underlying & Self::all_bits();
+        let mut reversed = T::Repr::zero();
+        while !bits.is_zero() {
+            let bit = bits.trailing_zeros();
+            bits = bits & !Self::mask(bit);
+            reversed = reversed | Self::mask(width - 1 - bit);
+        }
+        EnumSet { __enumset_underlying: reversed & Self::all_bits() }
+    }
+
+    /// Returns a set with the bits rotated left by `n` positions within
+    /// [`EnumSet::bit_width`], moving the value in bit `i` to bit `(i + n) % bit_width()`.
+    ///
+    /// Any bits moved to positions that don't correspond to a variant are dropped.
+    pub fn rotate_left(&self, n: u32) -> Self {
+        let width = Self::bit_width();
+        if width == 0 {
+            return Self::new();
+        }
+        let n = n % width;
+        let bits = self.__enumset_underlying & Self::all_bits();
+        let rotated = if n == 0 {
+            bits
+        } else {
+            bits.unsigned_shl(n) | bits.unsigned_shr(width - n)
+        };
+        EnumSet { __enumset_underlying: rotated & Self::all_bits() }
+    }
+
+    /// Returns a set with the bits rotated right by `n` positions within
+    /// [`EnumSet::bit_width`], moving the value in bit `i` to bit `(i - n) % bit_width()`.
+    ///
+    /// Any bits moved to positions that don't correspond to a variant are dropped.
+    pub fn rotate_right(&self, n: u32) -> Self {
+        let width = Self::bit_width();
+        if width == 0 {
+            return Self::new();
+        }
+        self.rotate_left(width - n % width)
+    }
+
+    /// Creates an iterator over the values in this set.
+    ///
+    /// Note that iterator invalidation is impossible as the iterator contains a copy of this type,
+    /// rather than holding a reference to it.
+    pub fn iter(&self) -> EnumSetIter<T> {
         EnumSetIter(*self, 0)
     }
+
+    /// Creates an iterator over the values in this set, from the highest discriminant to the
+    /// lowest.
+    ///
+    /// This visits values in the reverse order of [`EnumSet::iter`].
+    pub fn iter_rev(&self) -> EnumSetRevIter<T> {
+        EnumSetRevIter(self.__enumset_underlying)
+    }
+
+    /// Returns a `Vec` containing the values in this set, in the same order as
+    /// [`EnumSet::iter`].
+    ///
+    /// This method requires the `alloc` feature.
+    #[cfg(feature = "alloc")]
+    pub fn to_vec(&self) -> alloc::vec::Vec<T> {
+        self.iter().collect()
+    }
+
+    /// Returns a JSON array containing the index of each bit set, e.g. `[0,2,5]`.
+    ///
+    /// This is a minimal format for exchanging sets with other programs that does not require
+    /// `serde`. It can be read with [`EnumSet::from_json_indices`].
+    ///
+    /// This method requires the `alloc` feature.
+    #[cfg(feature = "alloc")]
+    pub fn to_json_indices(&self) -> alloc::string::String {
+        use core::fmt::Write;
+        let mut json = alloc::string::String::from("[");
+        for (i, (bit, _)) in self.iter_indexed().enumerate() {
+            if i != 0 {
+                json.push(',');
+            }
+            write!(json, "{}", bit).unwrap();
+        }
+        json.push(']');
+        json
+    }
+
+    /// Parses a JSON array of bit indices, as written by [`EnumSet::to_json_indices`].
+    ///
+    /// Whitespace is allowed around the brackets and indices. If the input is not an array of
+    /// non-negative integers, or an index doesn't correspond to an enum variant, this method will
+    /// return `None`.
+    pub fn from_json_indices(json: &str) -> Option<Self> {
+        let inner = json.trim().strip_prefix('[')?.strip_suffix(']')?.trim();
+        let mut set = Self::new();
+        if inner.is_empty() {
+            return Some(set);
+        }
+        for index in inner.split(',') {
+            let index = index.trim();
+            if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+                return None;
+            }
+            let bit = index.parse::<u32>().ok()?;
+            if !Self::is_valid_bit(bit) {
+                return None;
+            }
+            set.__enumset_underlying = set.__enumset_underlying | Self::mask(bit);
+        }
+        Some(set)
+    }
+
+    /// Returns an `ArrayVec` containing the values in this set, in the same order as
+    /// [`EnumSet::iter`].
+    ///
+    /// `N` must be at least [`EnumSet::variant_count`], or a compile-time error will occur. This
+    /// method requires the `arrayvec` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C }
+    /// let vec = (Enum::A | Enum::C).to_arrayvec::<3>();
+    /// assert_eq!(vec.as_slice(), [Enum::A, Enum::C]);
+    /// ```
+    #[cfg(feature = "arrayvec")]
+    pub fn to_arrayvec<const N: usize>(&self) -> arrayvec::ArrayVec<T, N> {
+        #[allow(clippy::let_unit_value)]
+        let () = __internal::AssertVariantCapacity::<T, N>::OK;
+        self.iter().collect()
+    }
+
+    /// Returns a `FixedBitSet` of length [`EnumSet::bit_width`] with the same bits set as this
+    /// set.
+    ///
+    /// This method requires the `fixedbitset` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C = 10 }
+    /// let bitset = (Enum::A | Enum::C).to_fixedbitset();
+    /// assert_eq!(bitset.len(), 11);
+    /// assert_eq!(bitset.ones().collect::<Vec<_>>(), [0, 10]);
+    /// ```
+    #[cfg(feature = "fixedbitset")]
+    pub fn to_fixedbitset(&self) -> fixedbitset::FixedBitSet {
+        let mut bitset = fixedbitset::FixedBitSet::with_capacity(Self::bit_width() as usize);
+        for bit in self.iter_bits() {
+            bitset.insert(bit as usize);
+        }
+        bitset
+    }
+
+    /// Attempts to construct a bitset from a `FixedBitSet` with the same bits set.
+    ///
+    /// The `FixedBitSet` may have any length. If a bit that doesn't correspond to an enum
+    /// variant is set, this method will return `None`. This method requires the `fixedbitset`
+    /// feature.
+    #[cfg(feature = "fixedbitset")]
+    pub fn try_from_fixedbitset(bitset: &fixedbitset::FixedBitSet) -> Option<Self> {
+        let bits = bitset.ones().map(|bit| u32::try_from(bit).unwrap_or(u32::MAX));
+        Self::try_from_indices(bits).ok()
+    }
+
+    /// Creates an iterator over each run of values with consecutive discriminants in this set.
+    ///
+    /// Each run is yielded as the first and last value in it, inclusive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C, D, E, F, G }
+    /// let set = Enum::A | Enum::B | Enum::C | Enum::E | Enum::G;
+    /// let ranges: Vec<_> = set.iter_ranges().collect();
+    /// assert_eq!(ranges, [(Enum::A, Enum::C), (Enum::E, Enum::E), (Enum::G, Enum::G)]);
+    /// ```
+    pub fn iter_ranges(&self) -> EnumSetRangeIter<T> {
+        EnumSetRangeIter(self.__enumset_underlying)
+    }
+
+    /// Creates an iterator over subsets of this set, each covering a fixed-size window of bits.
+    ///
+    /// The `k`th subset contains the values in this set whose index is in the range
+    /// `k * size .. (k + 1) * size`. Windows are yielded up to [`EnumSet::bit_width`], including
+    /// windows containing no values, and the last window may be partial.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is 0.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C, D, E, F, G }
+    /// let set = Enum::A | Enum::B | Enum::G;
+    /// let chunks: Vec<_> = set.chunks(3).collect();
+    /// assert_eq!(chunks, [Enum::A | Enum::B, EnumSet::new(), EnumSet::only(Enum::G)]);
+    /// ```
+    pub fn chunks(&self, size: u32) -> EnumSetChunks<T> {
+        assert!(size != 0, "chunk size must be non-zero");
+        EnumSetChunks(*self, 0, size)
+    }
+
+    /// Creates an iterator over the index of each bit set in the underlying representation.
+    ///
+    /// Unlike [`EnumSet::iter`], this does not convert the bits back into enum variants, and so
+    /// also yields any bits that do not correspond to a variant. This is intended as an aid for
+    /// debugging the layout of a bitset.
+    pub fn iter_bits(&self) -> EnumSetBitsIter<T> {
+        EnumSetBitsIter(self.__enumset_underlying)
+    }
+
+    /// Creates an iterator over the values in this set, alongside the index of the bit each
+    /// value is stored in.
+    ///
+    /// The index is the discriminant of the variant, and is suitable for indexing into arrays
+    /// with one element per bit of the set.
+    pub fn iter_indexed(&self) -> EnumSetIndexedIter<T> {
+        EnumSetIndexedIter(self.iter())
+    }
+
+    /// Creates an iterator over a set containing only that value, for each value in this set.
+    ///
+    /// This is the same as `self.iter().map(EnumSet::only)`.
+    pub fn singletons(&self) -> EnumSetSingletons<T> {
+        EnumSetSingletons(self.iter())
+    }
+
+    /// Creates an iterator over the 64-bit words of the underlying representation, starting with
+    /// the least significant word.
+    ///
+    /// This yields a single word for sets stored in a `u64` or smaller type, and two words for
+    /// sets stored in a `u128`. It is intended for consumers doing their own bulk bit
+    /// manipulation on the raw storage.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C, D, E, F, G }
+    /// let set = Enum::A | Enum::C;
+    /// assert_eq!(set.words().collect::<Vec<_>>(), [0b101]);
+    /// ```
+    pub fn words(&self) -> EnumSetWords<T> {
+        EnumSetWords(self.__enumset_underlying, 0)
+    }
+
+    /// Calls a fallible closure on each value in this set, stopping at the first error.
+    ///
+    /// Values are visited in the same order as [`EnumSet::iter`].
+    pub fn try_for_each<E, F: FnMut(T) -> Result<(), E>>(&self, mut f: F) -> Result<(), E> {
+        let mut bits = self.__enumset_underlying;
+        while !bits.is_zero() {
+            let bit = bits.trailing_zeros();
+            bits = bits & !Self::mask(bit);
+            f(unsafe { T::enum_from_u32(bit) })?;
+        }
+        Ok(())
+    }
+
+    /// Maps each value in this set to a value of another enum, collecting the results into a set
+    /// of that type.
+    ///
+    /// Values for which `map` returns `None` are dropped. This can be used to derive a set of a
+    /// coarse-grained enum from a set of a related fine-grained one.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// #[derive(EnumSetType, Debug)]
+    /// pub enum Permission { ReadFile, WriteFile, ReadNet, WriteNet, Debug }
+    /// #[derive(EnumSetType, Debug)]
+    /// pub enum Resource { File, Net }
+    ///
+    /// let set = Permission::ReadFile | Permission::WriteFile | Permission::Debug;
+    /// let resources = set.project(|p| match p {
+    ///     Permission::ReadFile | Permission::WriteFile => Some(Resource::File),
+    ///     Permission::ReadNet | Permission::WriteNet => Some(Resource::Net),
+    ///     Permission::Debug => None,
+    /// });
+    /// assert_eq!(resources, Resource::File);
+    /// ```
+    pub fn project<U: EnumSetType, F: FnMut(T) -> Option<U>>(&self, map: F) -> EnumSet<U> {
+        self.iter().filter_map(map).collect()
+    }
+
+    /// Returns the value in this set for which `f` returns the smallest key, or `None` if the
+    /// set is empty.
+    ///
+    /// If several values have the same smallest key, the one with the lowest discriminant is
+    /// returned.
+    pub fn min_by_key<K: Ord, F: FnMut(T) -> K>(&self, mut f: F) -> Option<T> {
+        self.iter().min_by_key(|&v| f(v))
+    }
+    /// Returns the value in this set for which `f` returns the largest key, or `None` if the
+    /// set is empty.
+    ///
+    /// If several values have the same largest key, the one with the highest discriminant is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C, D, E, F, G }
+    /// let priority = |v| match v { Enum::B => 10, Enum::D => 5, _ => 0 };
+    /// assert_eq!((Enum::A | Enum::B | Enum::D).max_by_key(priority), Some(Enum::B));
+    /// assert_eq!((Enum::A | Enum::D).max_by_key(priority), Some(Enum::D));
+    /// assert_eq!(EnumSet::<Enum>::empty().max_by_key(priority), None);
+    /// ```
+    pub fn max_by_key<K: Ord, F: FnMut(T) -> K>(&self, mut f: F) -> Option<T> {
+        self.iter().max_by_key(|&v| f(v))
+    }
+
+    /// Creates an iterator that removes each value from this set as it is yielded.
+    ///
+    /// Values are yielded in the same order as [`EnumSet::iter`]. If the iterator is dropped
+    /// before it is exhausted, any values that have not yet been yielded remain in the set.
+    pub fn drain(&mut self) -> EnumSetDrain<'_, T> {
+        EnumSetDrain(self)
+    }
+
+    /// Creates an iterator over the enum variants not in this set.
+    ///
+    /// Only valid variants of the enum are yielded, even for enums with "sparse" variants.
+    pub fn iter_complement(&self) -> EnumSetIter<T> {
+        EnumSetIter(self.complement(), 0)
+    }
+
+    /// Returns an array with one `bool` per bit of the set, indicating whether the variant
+    /// stored in that bit is present.
+    ///
+    /// `N` must be equal to [`EnumSet::bit_width`], or a compile-time error will occur.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C }
+    /// assert_eq!((Enum::A | Enum::C).to_bool_array(), [true, false, true]);
+    /// ```
+    ///
+    /// Using an array of the wrong length is an error:
+    ///
+    /// ```compile_fail
+    /// # use wasmer_enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C }
+    /// let array: [bool; 2] = (Enum::A | Enum::C).to_bool_array();
+    /// ```
+    pub fn to_bool_array<const N: usize>(&self) -> [bool; N] {
+        #[allow(clippy::let_unit_value)]
+        let () = AssertBitWidth::<T, N>::OK;
+        core::array::from_fn(|bit| self.has_bit(bit as u32))
+    }
+
+    /// Constructs a bitset from an array with one `bool` per bit of the set.
+    ///
+    /// `N` must be equal to [`EnumSet::bit_width`], or a compile-time error will occur. Any
+    /// `true` at an index that doesn't correspond to a variant is ignored.
+    pub fn from_bool_array<const N: usize>(array: [bool; N]) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = AssertBitWidth::<T, N>::OK;
+        let mut bits = T::Repr::zero();
+        for (bit, &present) in array.iter().enumerate() {
+            if present {
+                bits = bits | Self::mask(bit as u32);
+            }
+        }
+        EnumSet { __enumset_underlying: bits & Self::all_bits() }
+    }
+
+    /// Returns the underlying representation of this set as a native-endian byte array.
+    ///
+    /// This is the same layout the set has in memory, and is intended for embedding sets in
+    /// `#[repr(C)]` structs shared over FFI. `N` must be equal to [`EnumSet::REPR_SIZE_BYTES`],
+    /// or a compile-time error will occur.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] #[enumset(repr = "u16")] pub enum Enum { A, B, C }
+    /// let bytes: [u8; 2] = (Enum::A | Enum::C).as_byte_array();
+    /// assert_eq!(bytes, 5u16.to_ne_bytes());
+    /// ```
+    ///
+    /// Using an array of the wrong length is an error:
+    ///
+    /// ```compile_fail
+    /// # use wasmer_enumset::*;
+    /// # #[derive(EnumSetType, Debug)] #[enumset(repr = "u16")] pub enum Enum { A, B, C }
+    /// let bytes: [u8; 1] = (Enum::A | Enum::C).as_byte_array();
+    /// ```
+    pub fn as_byte_array<const N: usize>(&self) -> [u8; N] {
+        #[allow(clippy::let_unit_value)]
+        let () = AssertReprSize::<T, N>::OK;
+        let bytes = if cfg!(target_endian = "big") {
+            self.__enumset_underlying.to_be_bytes()
+        } else {
+            self.__enumset_underlying.to_le_bytes()
+        };
+        let mut array = [0; N];
+        array.copy_from_slice(bytes.as_ref());
+        array
+    }
+
+    /// Constructs a bitset from a native-endian byte array, as returned by
+    /// [`EnumSet::as_byte_array`].
+    ///
+    /// `N` must be equal to [`EnumSet::REPR_SIZE_BYTES`], or a compile-time error will occur. If
+    /// a bit that doesn't correspond to an enum variant is set, this method will panic.
+    pub fn from_byte_array<const N: usize>(array: [u8; N]) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = AssertReprSize::<T, N>::OK;
+        let mut bytes = T::Repr::zero().to_le_bytes();
+        bytes.as_mut().copy_from_slice(&array);
+        if cfg!(target_endian = "big") {
+            Self::from_be_bytes(bytes)
+        } else {
+            Self::from_le_bytes(bytes)
+        }
+    }
+
+    /// Constructs a bitset from an iterator of results, stopping at the first error.
+    ///
+    /// This is equivalent to collecting into a `Result<EnumSet<T>, E>`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C }
+    /// let parsed = ["A", "C"].iter().map(|s| match *s {
+    ///     "A" => Ok(Enum::A),
+    ///     "C" => Ok(Enum::C),
+    ///     s => Err(s),
+    /// });
+    /// assert_eq!(EnumSet::try_from_iter(parsed), Ok(Enum::A | Enum::C));
+    /// assert_eq!(EnumSet::try_from_iter([Ok(Enum::A), Err("D")]), Err("D"));
+    /// ```
+    pub fn try_from_iter<E, I: IntoIterator<Item = Result<T, E>>>(iter: I) -> Result<Self, E> {
+        iter.into_iter().collect()
+    }
+
+    /// Constructs a bitset from a slice of values that must not contain duplicates.
+    ///
+    /// If a value appears more than once, the first such value is returned as an error. This is
+    /// unlike the [`FromIterator`] implementation, which silently ignores duplicates.
+    pub fn from_slice_unique(slice: &[T]) -> Result<Self, T> {
+        let mut set = Self::new();
+        for &value in slice {
+            if !set.insert(value) {
+                return Err(value);
+            }
+        }
+        Ok(set)
+    }
+
+    /// Constructs a bitset from an iterator of raw bit indices.
+    ///
+    /// If an index doesn't correspond to an enum variant, the first such index is returned as
+    /// an error.
+    pub fn try_from_indices<I: IntoIterator<Item = u32>>(iter: I) -> Result<Self, u32> {
+        let mut set = Self::new();
+        for bit in iter {
+            if !Self::is_valid_bit(bit) {
+                return Err(bit);
+            }
+            set.__enumset_underlying = set.__enumset_underlying | Self::mask(bit);
+        }
+        Ok(set)
+    }
+
+    /// Constructs a bitset from an iterator of raw bit indices, ignoring indices that don't
+    /// correspond to an enum variant.
+    pub fn from_indices_truncated<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for bit in iter {
+            if Self::is_valid_bit(bit) {
+                set.__enumset_underlying = set.__enumset_underlying | Self::mask(bit);
+            }
+        }
+        set
+    }
+}
+
+/// Counts, for each category, how many of the given sets share at least one value with it.
+///
+/// The returned `Vec` has one count per entry of `categories`, in the same order. The sets are
+/// only iterated once. This function requires the `alloc` feature.
+///
+/// # Examples
+///
+/// ```rust
+/// # use enumset::*;
+/// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C, D, E, F, G }
+/// let sets = [Enum::A | Enum::B, Enum::C.into(), Enum::A | Enum::D, EnumSet::empty()];
+/// let categories = [Enum::A | Enum::C, EnumSet::only(Enum::D), Enum::F | Enum::G];
+/// assert_eq!(category_counts(sets, &categories), [3, 1, 0]);
+/// ```
+#[cfg(feature = "alloc")]
+pub fn category_counts<T: EnumSetType>(
+    sets: impl IntoIterator<Item = EnumSet<T>>, categories: &[EnumSet<T>],
+) -> alloc::vec::Vec<usize> {
+    let mut counts = alloc::vec![0; categories.len()];
+    for set in sets {
+        for (count, category) in counts.iter_mut().zip(categories) {
+            if !set.is_disjoint(*category) {
+                *count += 1;
+            }
+        }
+    }
+    counts
+}
+
+/// The error returned when constructing an [`EnumSet`] from bits that do not correspond to
+/// any variant of the enum.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct UnknownBitsError {
+    unknown_bits: u128,
+}
+impl UnknownBitsError {
+    /// Returns a mask of the bits that did not correspond to any variant.
+    pub fn unknown_bits(&self) -> u128 {
+        self.unknown_bits
+    }
+}
+impl fmt::Display for UnknownBitsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "enumset contains unknown bits: {:#x}", self.unknown_bits)
+    }
+}
+
+impl <T: EnumSetTypeWithName> EnumSet<T> {
+    /// Creates an iterator over the names of the values in this set.
+    ///
+    /// This method is only available for enums with the `#[enumset(with_name)]` attribute.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// #[derive(EnumSetType, Debug)]
+    /// #[enumset(with_name)]
+    /// pub enum Enum { A, B, C }
+    ///
+    /// assert_eq!(Enum::B.name(), "B");
+    /// let names: Vec<_> = (Enum::A | Enum::C).iter_names().collect();
+    /// assert_eq!(names, ["A", "C"]);
+    /// ```
+    pub fn iter_names(&self) -> EnumSetNameIter<T> {
+        EnumSetNameIter(self.iter())
+    }
+
+    /// Creates an iterator over the values in this set, alongside the name of each value.
+    ///
+    /// This method is only available for enums with the `#[enumset(with_name)]` attribute.
+    pub fn iter_named(&self) -> EnumSetNamedIter<T> {
+        EnumSetNamedIter(self.iter())
+    }
+}
+
+impl <T: EnumSetType + Debug> EnumSet<T> {
+    /// Returns a table showing, for each variant of the enum, the bit it is stored in and whether
+    /// it is present in this set.
+    ///
+    /// Each line of the table contains the bit index, the variant, and `[x]` if the variant is
+    /// present or `[ ]` otherwise. This is intended as an aid for debugging the layout of enums
+    /// with explicit discriminants, where the bit each variant is stored in isn't obvious.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B = 5, C = 10 }
+    /// let table = (Enum::A | Enum::C).bit_table().to_string();
+    /// assert_eq!(table, " 0  A  [x]\n 5  B  [ ]\n10  C  [x]");
+    /// ```
+    pub fn bit_table(&self) -> EnumSetBitTable<T> {
+        EnumSetBitTable(*self)
+    }
+}
+
+#[cfg(feature = "enum-map")]
+impl <T: EnumSetType + enum_map::Enum> EnumSet<T> {
+    /// Constructs a bitset containing each key of an `EnumMap` that maps to `true`.
+    ///
+    /// Keys are matched by value, so this does not depend on the two crates ordering variants
+    /// the same way. To collect the keys of a map populated with `Option`s, use
+    /// `EnumSet::from_predicate(|k| map[k].is_some())` instead.
+    ///
+    /// This method requires the `enum-map` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # use enum_map::{enum_map, Enum};
+    /// #[derive(EnumSetType, Enum, Debug)]
+    /// pub enum Key { A, B, C }
+    ///
+    /// let map = enum_map! { Key::A | Key::C => true, _ => false };
+    /// assert_eq!(EnumSet::from_enum_map(&map), Key::A | Key::C);
+    /// assert_eq!((Key::A | Key::C).to_enum_map(), map);
+    /// ```
+    pub fn from_enum_map(map: &enum_map::EnumMap<T, bool>) -> Self
+        where T: enum_map::EnumArray<bool>
+    {
+        map.iter().filter(|&(_, &present)| present).map(|(key, _)| key).collect()
+    }
+
+    /// Returns an `EnumMap` mapping each key in this set to `true`, and every other key to
+    /// `false`.
+    ///
+    /// This method requires the `enum-map` feature.
+    pub fn to_enum_map(&self) -> enum_map::EnumMap<T, bool> where T: enum_map::EnumArray<bool> {
+        enum_map::EnumMap::from_fn(|key| self.contains(key))
+    }
 }
 
 /// Helper macro for generating conversion functions.
@@ -419,7 +2122,7 @@ macro_rules! conversion_impls {
         $(for_num!(
             $underlying:ty, $underlying_str:expr, $from_fn:ident, $to_fn:ident,
             $from:ident $try_from:ident $from_truncated:ident
-            $to:ident $try_to:ident $to_truncated:ident
+            $to:ident $try_to:ident $to_truncated:ident $to_saturating:ident
         );)*
     ) => {
         impl <T : EnumSetType> EnumSet<T> {$(
@@ -453,6 +2156,16 @@ macro_rules! conversion_impls {
                 AsPrimitive::<$underlying>::as_(self.__enumset_underlying)
             }
 
+            #[doc = "Returns a saturated `"]
+            #[doc = $underlying_str]
+            #[doc = "` representing the elements of this set.\n\nIf the underlying bitset will \
+                     not fit in a `"]
+            #[doc = $underlying_str]
+            #[doc = "`, this method will return the maximum value of the type instead."]
+            pub fn $to_saturating(&self) -> $underlying {
+                self.$try_to().unwrap_or(<$underlying>::MAX)
+            }
+
             #[doc = "Constructs a bitset from a `"]
             #[doc = $underlying_str]
             #[doc = "`.\n\nIf a bit that doesn't correspond to an enum variant is set, this \
@@ -488,20 +2201,222 @@ macro_rules! conversion_impls {
 }
 conversion_impls! {
     for_num!(u8, "u8", from_u8, to_u8,
-             from_u8 try_from_u8 from_u8_truncated as_u8 try_as_u8 as_u8_truncated);
+             from_u8 try_from_u8 from_u8_truncated
+             as_u8 try_as_u8 as_u8_truncated as_u8_saturating);
     for_num!(u16, "u16", from_u16, to_u16,
-             from_u16 try_from_u16 from_u16_truncated as_u16 try_as_u16 as_u16_truncated);
+             from_u16 try_from_u16 from_u16_truncated
+             as_u16 try_as_u16 as_u16_truncated as_u16_saturating);
     for_num!(u32, "u32", from_u32, to_u32,
-             from_u32 try_from_u32 from_u32_truncated as_u32 try_as_u32 as_u32_truncated);
+             from_u32 try_from_u32 from_u32_truncated
+             as_u32 try_as_u32 as_u32_truncated as_u32_saturating);
     for_num!(u64, "u64", from_u64, to_u64,
-             from_u64 try_from_u64 from_u64_truncated as_u64 try_as_u64 as_u64_truncated);
+             from_u64 try_from_u64 from_u64_truncated
+             as_u64 try_as_u64 as_u64_truncated as_u64_saturating);
     for_num!(u128, "u128", from_u128, to_u128,
-             from_u128 try_from_u128 from_u128_truncated as_u128 try_as_u128 as_u128_truncated);
+             from_u128 try_from_u128 from_u128_truncated
+             as_u128 try_as_u128 as_u128_truncated as_u128_saturating);
     for_num!(usize, "usize", from_usize, to_usize,
+             from_usize try_from_usize from_usize_truncated
+             as_usize try_as_usize as_usize_truncated as_usize_saturating);
+}
+
+/// Helper macro for generating byte array conversion functions.
+macro_rules! bytes_impls {
+    (
+        $(for_endian!(
+            $endian_str:expr, $to:ident, $from:ident, $try_from:ident, $from_truncated:ident
+        );)*
+    ) => {
+        impl <T : EnumSetType> EnumSet<T> {$(
+            #[doc = "Returns the elements of this set as a "]
+            #[doc = $endian_str]
+            #[doc = " byte array.\n\nThe array has a length of [`EnumSet::REPR_SIZE_BYTES`]."]
+            pub fn $to(&self) -> <T::Repr as EnumSetTypeRepr>::Bytes {
+                self.__enumset_underlying.$to()
+            }
+
+            #[doc = "Constructs a bitset from a "]
+            #[doc = $endian_str]
+            #[doc = " byte array.\n\nIf a bit that doesn't correspond to an enum variant is set, \
+                     this method will panic."]
+            pub fn $from(bytes: <T::Repr as EnumSetTypeRepr>::Bytes) -> Self {
+                Self::$try_from(bytes).expect("Bitset contains invalid variants.")
+            }
+
+            #[doc = "Attempts to constructs a bitset from a "]
+            #[doc = $endian_str]
+            #[doc = " byte array.\n\nIf a bit that doesn't correspond to an enum variant is set, \
+                     this method will return `None`."]
+            pub fn $try_from(bytes: <T::Repr as EnumSetTypeRepr>::Bytes) -> Option<Self> {
+                let bits = T::Repr::$from(bytes);
+                if (bits & !Self::all_bits()) == T::Repr::zero() {
+                    Some(EnumSet { __enumset_underlying: bits })
+                } else {
+                    None
+                }
+            }
+
+            #[doc = "Constructs a bitset from a "]
+            #[doc = $endian_str]
+            #[doc = " byte array, ignoring invalid variants."]
+            pub fn $from_truncated(bytes: <T::Repr as EnumSetTypeRepr>::Bytes) -> Self {
+                EnumSet { __enumset_underlying: T::Repr::$from(bytes) & Self::all_bits() }
+            }
+        )*}
+    }
+}
+bytes_impls! {
+    for_endian!("little-endian", to_le_bytes,
+                from_le_bytes, try_from_le_bytes, from_le_bytes_truncated);
+    for_endian!("big-endian", to_be_bytes,
+                from_be_bytes, try_from_be_bytes, from_be_bytes_truncated);
+}
+
+/// Helper macro for generating signed conversion functions on top of the unsigned ones.
+macro_rules! signed_conversion_impls {
+    (
+        $(for_num!(
+            $signed:ty, $unsigned:ty, $signed_str:expr,
+            $from:ident $try_from:ident $from_truncated:ident
+            $to:ident $try_to:ident $to_truncated:ident,
+            $from_u:ident $try_from_u:ident $from_truncated_u:ident
+            $to_u:ident $try_to_u:ident $to_truncated_u:ident
+        );)*
+    ) => {
+        impl <T : EnumSetType> EnumSet<T> {$(
+            #[doc = "Returns a `"]
+            #[doc = $signed_str]
+            #[doc = "` representing the elements of this set.\n\nThe bits of the set are \
+                     reinterpreted as a signed integer, rather than converted numerically. If \
+                     the underlying bitset will not fit in a `"]
+            #[doc = $signed_str]
+            #[doc = "`, this method will panic."]
+            pub fn $to(&self) -> $signed {
+                self.$to_u() as $signed
+            }
+
+            #[doc = "Tries to return a `"]
+            #[doc = $signed_str]
+            #[doc = "` representing the elements of this set.\n\nThe bits of the set are \
+                     reinterpreted as a signed integer, rather than converted numerically. If \
+                     the underlying bitset will not fit in a `"]
+            #[doc = $signed_str]
+            #[doc = "`, this method will instead return `None`."]
+            pub fn $try_to(&self) -> Option<$signed> {
+                self.$try_to_u().map(|bits| bits as $signed)
+            }
+
+            #[doc = "Returns a truncated `"]
+            #[doc = $signed_str]
+            #[doc = "` representing the elements of this set.\n\nThe bits of the set are \
+                     reinterpreted as a signed integer, rather than converted numerically. If \
+                     the underlying bitset will not fit in a `"]
+            #[doc = $signed_str]
+            #[doc = "`, this method will truncate any bits that don't fit."]
+            pub fn $to_truncated(&self) -> $signed {
+                self.$to_truncated_u() as $signed
+            }
+
+            #[doc = "Constructs a bitset from a `"]
+            #[doc = $signed_str]
+            #[doc = "`.\n\nThe bits of the integer are reinterpreted as an unsigned bitset, \
+                     rather than converted numerically. If a bit that doesn't correspond to an \
+                     enum variant is set, this method will panic."]
+            pub fn $from(bits: $signed) -> Self {
+                Self::$from_u(bits as $unsigned)
+            }
+
+            #[doc = "Attempts to constructs a bitset from a `"]
+            #[doc = $signed_str]
+            #[doc = "`.\n\nThe bits of the integer are reinterpreted as an unsigned bitset, \
+                     rather than converted numerically. If a bit that doesn't correspond to an \
+                     enum variant is set, this method will return `None`."]
+            pub fn $try_from(bits: $signed) -> Option<Self> {
+                Self::$try_from_u(bits as $unsigned)
+            }
+
+            #[doc = "Constructs a bitset from a `"]
+            #[doc = $signed_str]
+            #[doc = "`, ignoring invalid variants.\n\nThe bits of the integer are reinterpreted \
+                     as an unsigned bitset, rather than converted numerically."]
+            pub fn $from_truncated(bits: $signed) -> Self {
+                Self::$from_truncated_u(bits as $unsigned)
+            }
+        )*}
+    }
+}
+signed_conversion_impls! {
+    for_num!(i8, u8, "i8",
+             from_i8 try_from_i8 from_i8_truncated as_i8 try_as_i8 as_i8_truncated,
+             from_u8 try_from_u8 from_u8_truncated as_u8 try_as_u8 as_u8_truncated);
+    for_num!(i16, u16, "i16",
+             from_i16 try_from_i16 from_i16_truncated as_i16 try_as_i16 as_i16_truncated,
+             from_u16 try_from_u16 from_u16_truncated as_u16 try_as_u16 as_u16_truncated);
+    for_num!(i32, u32, "i32",
+             from_i32 try_from_i32 from_i32_truncated as_i32 try_as_i32 as_i32_truncated,
+             from_u32 try_from_u32 from_u32_truncated as_u32 try_as_u32 as_u32_truncated);
+    for_num!(i64, u64, "i64",
+             from_i64 try_from_i64 from_i64_truncated as_i64 try_as_i64 as_i64_truncated,
+             from_u64 try_from_u64 from_u64_truncated as_u64 try_as_u64 as_u64_truncated);
+    for_num!(i128, u128, "i128",
+             from_i128 try_from_i128 from_i128_truncated as_i128 try_as_i128 as_i128_truncated,
+             from_u128 try_from_u128 from_u128_truncated as_u128 try_as_u128 as_u128_truncated);
+    for_num!(isize, usize, "isize",
+             from_isize try_from_isize from_isize_truncated
+             as_isize try_as_isize as_isize_truncated,
              from_usize try_from_usize from_usize_truncated
              as_usize try_as_usize as_usize_truncated);
 }
 
+/// Helper macro for implementing `TryFrom` for integer types.
+macro_rules! try_from_impls {
+    ($($underlying:ty),* $(,)?) => {$(
+        impl <T: EnumSetType> TryFrom<$underlying> for EnumSet<T> {
+            type Error = UnknownBitsError;
+
+            /// Constructs a bitset from an integer, failing if a bit that doesn't correspond to
+            /// an enum variant is set.
+            fn try_from(bits: $underlying) -> Result<Self, Self::Error> {
+                let all_bits: u128 = Self::all_bits().as_();
+                let unknown_bits = bits as u128 & !all_bits;
+                if unknown_bits == 0 {
+                    let bits = <T::Repr as EnumSetTypeRepr>::from_u128(bits as u128);
+                    Ok(EnumSet { __enumset_underlying: bits })
+                } else {
+                    Err(UnknownBitsError { unknown_bits })
+                }
+            }
+        }
+    )*}
+}
+try_from_impls!(u8, u16, u32, u64, u128, usize);
+
+impl <T: EnumSetType> From<EnumSet<T>> for u128 {
+    /// Returns a `u128` representing the elements of a set.
+    ///
+    /// This conversion is implemented for every `EnumSet`, as every `EnumSet` fits in a `u128`.
+    /// Conversions into smaller integer types are only implemented for sets whose underlying
+    /// representation fits in them.
+    fn from(set: EnumSet<T>) -> Self {
+        set.__enumset_underlying.as_()
+    }
+}
+
+macro_rules! narrow_from_impls {
+    ($($underlying:ty),* $(,)?) => {$(
+        impl <T: EnumSetType> From<EnumSet<T>> for $underlying where T::Repr: Into<$underlying> {
+            /// Returns an integer representing the elements of a set.
+            ///
+            /// This is only implemented when the underlying bitset fits in the integer type, so
+            /// the conversion never truncates.
+            fn from(set: EnumSet<T>) -> Self {
+                set.__enumset_underlying.into()
+            }
+        }
+    )*}
+}
+narrow_from_impls!(u8, u16, u32, u64);
+
 impl <T: EnumSetType> Default for EnumSet<T> {
     /// Returns an empty set.
     fn default() -> Self {
@@ -583,6 +2498,10 @@ impl <T: EnumSetType> PartialEq<T> for EnumSet<T> {
     }
 }
 impl <T: EnumSetType + Debug> Debug for EnumSet<T> {
+    /// Formats the set as a list of its values, e.g. `EnumSet(A | B)`.
+    ///
+    /// With the alternate flag (`{:#?}`), the underlying bits are also written in hexadecimal,
+    /// e.g. `EnumSet(A | B) [0x3]`.
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
         let mut is_first = true;
         f.write_str("EnumSet(")?;
@@ -591,65 +2510,412 @@ impl <T: EnumSetType + Debug> Debug for EnumSet<T> {
             is_first = false;
             v.fmt(f)?;
         }
-        f.write_str(")")?;
-        Ok(())
+        f.write_str(")")?;
+        if f.alternate() {
+            let bits: u128 = self.__enumset_underlying.as_();
+            write!(f, " [{:#x}]", bits)?;
+        }
+        Ok(())
+    }
+}
+
+/// The table returned by [`EnumSet::bit_table`].
+#[derive(Copy, Clone, Debug)]
+pub struct EnumSetBitTable<T: EnumSetType>(EnumSet<T>);
+impl <T: EnumSetType + Debug> fmt::Display for EnumSetBitTable<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let max_bit = EnumSet::<T>::bit_width().saturating_sub(1);
+        let width = if max_bit >= 100 { 3 } else if max_bit >= 10 { 2 } else { 1 };
+        for (i, (bit, v)) in EnumSet::<T>::all().iter_indexed().enumerate() {
+            if i != 0 { f.write_str("\n")?; }
+            let mark = if self.0.has_bit(bit) { "[x]" } else { "[ ]" };
+            write!(f, "{:>width$}  {:?}  {}", bit, v, mark, width = width)?;
+        }
+        Ok(())
+    }
+}
+
+impl <T: EnumSetType> Hash for EnumSet<T> {
+    /// Hashes the underlying integer representation of this set.
+    ///
+    /// The resulting hash depends on the width of the representation, and so may change when
+    /// variants are added to the enum. Use [`EnumSet::canonical_hash_bits`] if a hash that is
+    /// stable across such changes is required.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.__enumset_underlying.hash(state)
+    }
+}
+impl <T: EnumSetType> PartialOrd for EnumSet<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.__enumset_underlying.partial_cmp(&other.__enumset_underlying)
+    }
+}
+impl <T: EnumSetType> Ord for EnumSet<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.__enumset_underlying.cmp(&other.__enumset_underlying)
+    }
+}
+
+/// The integer format used to serialize an `EnumSet` with [`EnumSet::serialize_as_width`].
+///
+/// Each variant corresponds to a value of the `#[enumset(serialize_repr = "...")]` attribute.
+#[cfg(feature = "serde")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ReprWidth {
+    /// Serialize the bitset as a `u8`.
+    U8,
+    /// Serialize the bitset as a `u16`.
+    U16,
+    /// Serialize the bitset as a `u32`.
+    U32,
+    /// Serialize the bitset as a `u64`.
+    U64,
+    /// Serialize the bitset as a `u128`.
+    U128,
+    /// Serialize the bitset as a LEB128 varint, written as a tuple of bytes.
+    Varint,
+}
+#[cfg(feature = "serde")]
+impl ReprWidth {
+    /// Returns the number of bits that can be stored in this format.
+    pub fn bits(self) -> u32 {
+        match self {
+            ReprWidth::U8 => 8,
+            ReprWidth::U16 => 16,
+            ReprWidth::U32 => 32,
+            ReprWidth::U64 => 64,
+            ReprWidth::U128 | ReprWidth::Varint => 128,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl <T: EnumSetType> EnumSet<T> {
+    /// Serializes this set as an integer of a width chosen at runtime.
+    ///
+    /// This produces the same output as the `#[enumset(serialize_repr = "...")]` attribute with
+    /// the corresponding width, and can be used when the width depends on something only known
+    /// at runtime, such as a negotiated protocol version. It ignores the serialization attributes
+    /// of the enum, and so is usually called from a `#[serde(serialize_with = "...")]` function.
+    ///
+    /// Unlike the attribute, which is checked at compile time, this returns an error if a value
+    /// in the set is stored in a bit that does not fit in `width`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use enumset::*;
+    /// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C, D, E, F, G, H, I, J }
+    /// let set = Enum::A | Enum::C;
+    /// let mut json = Vec::new();
+    /// set.serialize_as_width(ReprWidth::U8, &mut serde_json::Serializer::new(&mut json))?;
+    /// assert_eq!(json, b"5");
+    ///
+    /// let mut de = serde_json::Deserializer::from_slice(&json);
+    /// assert_eq!(EnumSet::<Enum>::deserialize_as_width(ReprWidth::U8, &mut de)?, set);
+    ///
+    /// let mut json = Vec::new();
+    /// let ser = &mut serde_json::Serializer::new(&mut json);
+    /// assert!((Enum::A | Enum::J).serialize_as_width(ReprWidth::U8, ser).is_err());
+    /// # Ok::<(), serde_json::Error>(())
+    /// ```
+    pub fn serialize_as_width<S: serde::Serializer>(
+        &self, width: ReprWidth, ser: S,
+    ) -> Result<S::Ok, S::Error> {
+        let bits: u128 = self.__enumset_underlying.as_();
+        if width.bits() < 128 && bits >> width.bits() != 0 {
+            use serde::ser::Error;
+            return Err(S::Error::custom("enumset does not fit in the serialized width"));
+        }
+        __internal::serialize_with_width(bits, width, ser)
+    }
+
+    /// Deserializes a set written by [`EnumSet::serialize_as_width`] with the same width.
+    ///
+    /// Unknown bits are ignored and silently removed from the bitset, the same as when no
+    /// `#[enumset(serialize_deny_unknown)]` attribute is present.
+    pub fn deserialize_as_width<'de, D: serde::Deserializer<'de>>(
+        width: ReprWidth, de: D,
+    ) -> Result<Self, D::Error> {
+        let bits = __internal::deserialize_with_width(width, de)?;
+        Ok(Self::from_u128_truncated(bits))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl <T: EnumSetType> Serialize for EnumSet<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        T::serialize(*self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl <'de, T: EnumSetType> Deserialize<'de> for EnumSet<T> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer)
+    }
+}
+
+/// The iterator used by [`EnumSet`]s.
+#[derive(Clone, Debug)]
+pub struct EnumSetIter<T: EnumSetType>(EnumSet<T>, u32);
+impl <T: EnumSetType> EnumSetIter<T> {
+    /// Returns the next value in the iterator without advancing it.
+    ///
+    /// Unlike wrapping the iterator in [`Peekable`](core::iter::Peekable), this keeps the
+    /// iterator `Clone` and does not store the peeked value.
+    pub fn peek(&self) -> Option<T> {
+        let left = self.0.__enumset_underlying & !EnumSet::<T>::partial_bits(self.1);
+        if left.is_zero() {
+            None
+        } else {
+            unsafe { Some(T::enum_from_u32(left.trailing_zeros())) }
+        }
+    }
+
+    fn next_bit(&mut self) -> Option<u32> {
+        while self.1 < EnumSet::<T>::bit_width() {
+            let bit = self.1;
+            self.1 += 1;
+            if self.0.has_bit(bit) {
+                return Some(bit)
+            }
+        }
+        None
+    }
+}
+impl <T: EnumSetType> Iterator for EnumSetIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_bit().map(|bit| unsafe { T::enum_from_u32(bit) })
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left_mask = !EnumSet::<T>::partial_bits(self.1);
+        let left = (self.0.__enumset_underlying & left_mask).count_ones() as usize;
+        (left, Some(left))
+    }
+}
+
+impl<T: EnumSetType> ExactSizeIterator for EnumSetIter<T> {}
+
+/// The iterator returned by [`EnumSet::iter_rev`].
+#[derive(Clone, Debug)]
+pub struct EnumSetRevIter<T: EnumSetType>(T::Repr);
+impl <T: EnumSetType> Iterator for EnumSetRevIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let value = EnumSet::<T>::highest_in(self.0);
+        if let Some(value) = value {
+            self.0 = self.0 & !EnumSet::<T>::mask(value.enum_into_u32());
+        }
+        value
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.0.count_ones() as usize;
+        (left, Some(left))
+    }
+}
+
+impl<T: EnumSetType> ExactSizeIterator for EnumSetRevIter<T> {}
+
+/// The iterator returned by [`EnumSet::iter_names`].
+#[derive(Clone, Debug)]
+pub struct EnumSetNameIter<T: EnumSetType>(EnumSetIter<T>);
+impl <T: EnumSetTypeWithName> Iterator for EnumSetNameIter<T> {
+    type Item = &'static str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(T::variant_name)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T: EnumSetTypeWithName> ExactSizeIterator for EnumSetNameIter<T> {}
+
+/// The iterator returned by [`EnumSet::iter_named`].
+#[derive(Clone, Debug)]
+pub struct EnumSetNamedIter<T: EnumSetType>(EnumSetIter<T>);
+impl <T: EnumSetTypeWithName> Iterator for EnumSetNamedIter<T> {
+    type Item = (T, &'static str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|v| (v, v.variant_name()))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<T: EnumSetTypeWithName> ExactSizeIterator for EnumSetNamedIter<T> {}
+
+/// The iterator returned by [`EnumSet::iter_ranges`].
+#[derive(Clone, Debug)]
+pub struct EnumSetRangeIter<T: EnumSetType>(T::Repr);
+impl <T: EnumSetType> Iterator for EnumSetRangeIter<T> {
+    type Item = (T, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.is_zero() {
+            None
+        } else {
+            let start = self.0.trailing_zeros();
+            let len = (!self.0.unsigned_shr(start)).trailing_zeros().min(T::Repr::WIDTH - start);
+            let end = start + len;
+            let run = EnumSet::<T>::partial_bits(end) & !EnumSet::<T>::partial_bits(start);
+            self.0 = self.0 & !run;
+            unsafe { Some((T::enum_from_u32(start), T::enum_from_u32(end - 1))) }
+        }
+    }
+}
+
+/// The iterator returned by [`EnumSet::power_set`].
+#[derive(Clone, Debug)]
+pub struct EnumSetPowerSet<T: EnumSetType>(Option<T::Repr>);
+impl <T: EnumSetType> Iterator for EnumSetPowerSet<T> {
+    type Item = EnumSet<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.map(|bits| {
+            let next = bits.wrapping_sub(&T::ALL_BITS) & T::ALL_BITS;
+            self.0 = if next.is_zero() { None } else { Some(next) };
+            EnumSet { __enumset_underlying: bits }
+        })
+    }
+}
+
+/// The iterator returned by [`EnumSet::chunks`].
+#[derive(Clone, Debug)]
+pub struct EnumSetChunks<T: EnumSetType>(EnumSet<T>, u32, u32);
+impl <T: EnumSetType> Iterator for EnumSetChunks<T> {
+    type Item = EnumSet<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.1;
+        if start >= EnumSet::<T>::bit_width() {
+            None
+        } else {
+            let end = start.saturating_add(self.2);
+            self.1 = end;
+            let window = EnumSet::<T>::partial_bits(end) & !EnumSet::<T>::partial_bits(start);
+            Some(EnumSet { __enumset_underlying: self.0.__enumset_underlying & window })
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left_bits = EnumSet::<T>::bit_width().saturating_sub(self.1);
+        let left = (left_bits / self.2 + (left_bits % self.2 != 0) as u32) as usize;
+        (left, Some(left))
     }
 }
 
-impl <T: EnumSetType> Hash for EnumSet<T> {
-    fn hash<H: Hasher>(&self, state: &mut H) {
-        self.__enumset_underlying.hash(state)
+impl<T: EnumSetType> ExactSizeIterator for EnumSetChunks<T> {}
+
+/// The iterator returned by [`EnumSet::iter_bits`].
+#[derive(Clone, Debug)]
+pub struct EnumSetBitsIter<T: EnumSetType>(T::Repr);
+impl <T: EnumSetType> Iterator for EnumSetBitsIter<T> {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.is_zero() {
+            None
+        } else {
+            let bit = self.0.trailing_zeros();
+            self.0 = self.0 & !EnumSet::<T>::mask(bit);
+            Some(bit)
+        }
     }
-}
-impl <T: EnumSetType> PartialOrd for EnumSet<T> {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.__enumset_underlying.partial_cmp(&other.__enumset_underlying)
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = self.0.count_ones() as usize;
+        (left, Some(left))
     }
 }
-impl <T: EnumSetType> Ord for EnumSet<T> {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.__enumset_underlying.cmp(&other.__enumset_underlying)
+
+impl<T: EnumSetType> ExactSizeIterator for EnumSetBitsIter<T> {}
+
+/// The iterator returned by [`EnumSet::iter_indexed`].
+#[derive(Clone, Debug)]
+pub struct EnumSetIndexedIter<T: EnumSetType>(EnumSetIter<T>);
+impl <T: EnumSetType> Iterator for EnumSetIndexedIter<T> {
+    type Item = (u32, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_bit().map(|bit| (bit, unsafe { T::enum_from_u32(bit) }))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
     }
 }
 
-#[cfg(feature = "serde")]
-impl <T: EnumSetType> Serialize for EnumSet<T> {
-    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
-        T::serialize(*self, serializer)
+impl<T: EnumSetType> ExactSizeIterator for EnumSetIndexedIter<T> {}
+
+/// The iterator returned by [`EnumSet::singletons`].
+#[derive(Clone, Debug)]
+pub struct EnumSetSingletons<T: EnumSetType>(EnumSetIter<T>);
+impl <T: EnumSetType> Iterator for EnumSetSingletons<T> {
+    type Item = EnumSet<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(EnumSet::only)
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
     }
 }
 
-#[cfg(feature = "serde")]
-impl <'de, T: EnumSetType> Deserialize<'de> for EnumSet<T> {
-    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
-        T::deserialize(deserializer)
+impl<T: EnumSetType> ExactSizeIterator for EnumSetSingletons<T> {}
+
+/// The iterator returned by [`EnumSet::words`].
+#[derive(Clone, Debug)]
+pub struct EnumSetWords<T: EnumSetType>(T::Repr, u32);
+impl <T: EnumSetType> EnumSetWords<T> {
+    const WORD_COUNT: u32 = (T::Repr::WIDTH + 63) / 64;
+}
+impl <T: EnumSetType> Iterator for EnumSetWords<T> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.1 >= Self::WORD_COUNT {
+            None
+        } else {
+            let word = (self.0 >> (self.1 as usize * 64)).as_();
+            self.1 += 1;
+            Some(word)
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let left = (Self::WORD_COUNT - self.1) as usize;
+        (left, Some(left))
     }
 }
 
-/// The iterator used by [`EnumSet`]s.
-#[derive(Clone, Debug)]
-pub struct EnumSetIter<T: EnumSetType>(EnumSet<T>, u32);
-impl <T: EnumSetType> Iterator for EnumSetIter<T> {
+impl<T: EnumSetType> ExactSizeIterator for EnumSetWords<T> {}
+
+/// The iterator returned by [`EnumSet::drain`].
+#[derive(Debug)]
+pub struct EnumSetDrain<'a, T: EnumSetType>(&'a mut EnumSet<T>);
+impl <T: EnumSetType> Iterator for EnumSetDrain<'_, T> {
     type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        while self.1 < EnumSet::<T>::bit_width() {
-            let bit = self.1;
-            self.1 += 1;
-            if self.0.has_bit(bit) {
-                return unsafe { Some(T::enum_from_u32(bit)) }
-            }
+        if self.0.is_empty() {
+            None
+        } else {
+            let bit = self.0.__enumset_underlying.trailing_zeros();
+            self.0.__enumset_underlying = self.0.__enumset_underlying & !EnumSet::<T>::mask(bit);
+            unsafe { Some(T::enum_from_u32(bit)) }
         }
-        None
     }
     fn size_hint(&self) -> (usize, Option<usize>) {
-        let left_mask = !EnumSet::<T>::partial_bits(self.1);
-        let left = (self.0.__enumset_underlying & left_mask).count_ones() as usize;
+        let left = self.0.len();
         (left, Some(left))
     }
 }
 
-impl<T: EnumSetType> ExactSizeIterator for EnumSetIter<T> {}
+impl<T: EnumSetType> ExactSizeIterator for EnumSetDrain<'_, T> {}
 
 impl<T: EnumSetType> Extend<T> for EnumSet<T> {
     fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
@@ -679,6 +2945,112 @@ impl<T: EnumSetType> FromIterator<EnumSet<T>> for EnumSet<T> {
     }
 }
 
+impl<T: EnumSetType> Sum for EnumSet<T> {
+    /// Returns the union of all sets in the iterator.
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(EnumSet::empty(), |acc, v| acc | v)
+    }
+}
+
+impl<'a, T: EnumSetType> Sum<&'a EnumSet<T>> for EnumSet<T> {
+    /// Returns the union of all sets in the iterator.
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(EnumSet::empty(), |acc, v| acc | *v)
+    }
+}
+
+impl<T: EnumSetType> Product for EnumSet<T> {
+    /// Returns the intersection of all sets in the iterator.
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(EnumSet::all(), |acc, v| acc & v)
+    }
+}
+
+impl<'a, T: EnumSetType> Product<&'a EnumSet<T>> for EnumSet<T> {
+    /// Returns the intersection of all sets in the iterator.
+    fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.fold(EnumSet::all(), |acc, v| acc & *v)
+    }
+}
+
+/// Implements conversions between an `EnumSet` and a flags type generated by the `bitflags`
+/// crate.
+///
+/// The syntax used is `enum_set_bitflags!(Enum, Flags)`. This implements `From<EnumSet<Enum>>`
+/// for `Flags` and `From<Flags>` for `EnumSet<Enum>`, converting via the underlying integer
+/// representation using [`EnumSet::as_repr`] and [`EnumSet::from_repr_truncated`]. The flags
+/// type must use the same integer type as the `EnumSet`, which can be forced with the
+/// `#[enumset(repr = "...")]` attribute, and each flag must use the same bit as the
+/// corresponding enum variant. Unknown bits are ignored in both directions.
+///
+/// # Examples
+///
+/// ```rust
+/// # use enumset::*;
+/// # use bitflags::bitflags;
+/// #[derive(EnumSetType, Debug)]
+/// #[enumset(repr = "u32")]
+/// pub enum Flag { A, B, C }
+///
+/// bitflags! {
+///     pub struct Flags: u32 {
+///         const A = 1 << 0;
+///         const B = 1 << 1;
+///         const C = 1 << 2;
+///     }
+/// }
+///
+/// enum_set_bitflags!(Flag, Flags);
+///
+/// assert_eq!(Flags::from(Flag::A | Flag::C), Flags::A | Flags::C);
+/// assert_eq!(EnumSet::from(Flags::B), Flag::B);
+/// ```
+///
+/// To reject unknown bits instead, or to convert flags of a different width than the `EnumSet`,
+/// widen the bits to a `u128` and use [`EnumSet::from_raw_bits_checked`]. This works regardless
+/// of either type's integer width, and returns a mask of the bits without a variant on failure.
+/// The [`TryFrom<u128>`](TryFrom) implementation of `EnumSet` does the same, reporting the bits
+/// through [`UnknownBitsError::unknown_bits`]:
+///
+/// ```rust
+/// # use enumset::*;
+/// # use bitflags::bitflags;
+/// #[derive(EnumSetType, Debug)]
+/// pub enum Flag { A, B, C }
+///
+/// bitflags! {
+///     pub struct Flags: u64 {
+///         const A = 1 << 0;
+///         const C = 1 << 2;
+///         const UNKNOWN = 1 << 40;
+///     }
+/// }
+///
+/// let set = EnumSet::<Flag>::from_raw_bits_checked((Flags::A | Flags::C).bits() as u128);
+/// assert_eq!(set, Ok(Flag::A | Flag::C));
+/// let invalid = EnumSet::<Flag>::from_raw_bits_checked(Flags::all().bits() as u128);
+/// assert_eq!(invalid, Err(1 << 40));
+/// ```
+#[macro_export]
+macro_rules! enum_set_bitflags {
+    ($enum_ty:ty, $flags_ty:ty $(,)?) => {
+        impl $crate::__internal::core_export::convert::From<$crate::EnumSet<$enum_ty>>
+            for $flags_ty
+        {
+            fn from(set: $crate::EnumSet<$enum_ty>) -> Self {
+                <$flags_ty>::from_bits_truncate(set.as_repr())
+            }
+        }
+        impl $crate::__internal::core_export::convert::From<$flags_ty>
+            for $crate::EnumSet<$enum_ty>
+        {
+            fn from(flags: $flags_ty) -> Self {
+                $crate::EnumSet::from_repr_truncated(flags.bits())
+            }
+        }
+    };
+}
+
 /// Creates a EnumSet literal, which can be used in const contexts.
 ///
 /// The syntax used is `enum_set!(Type::A | Type::B | Type::C)`. Each variant must be of the same
@@ -693,6 +3065,29 @@ impl<T: EnumSetType> FromIterator<EnumSet<T>> for EnumSet<T> {
 /// assert_eq!(CONST_SET, Enum::A | Enum::B);
 /// ```
 ///
+/// Variants may also be separated by commas, as in `enum_set!(Type::A, Type::B, Type::C)`, but
+/// the two separators cannot be mixed in one literal:
+///
+/// ```rust
+/// # use enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C }
+/// const CONST_SET: EnumSet<Enum> = enum_set!(Enum::A, Enum::B,);
+/// assert_eq!(CONST_SET, Enum::A | Enum::B);
+/// ```
+///
+/// Variables may be used in place of variants. This allows singleton sets to be constructed in
+/// a `const fn`, where [`EnumSet::only`] cannot be used:
+///
+/// ```rust
+/// # use enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C }
+/// const fn only(value: Enum) -> EnumSet<Enum> {
+///     enum_set!(value)
+/// }
+/// const CONST_SET: EnumSet<Enum> = only(Enum::B);
+/// assert_eq!(CONST_SET, Enum::B);
+/// ```
+///
 /// This macro is strongly typed. For example, the following will not compile:
 ///
 /// ```compile_fail
@@ -710,8 +3105,294 @@ macro_rules! enum_set {
         $crate::__internal::EnumSetSameTypeHack {
             unified: &[$($value,)*],
             enum_set: $crate::EnumSet {
-                __enumset_underlying: 0 $(| (1 << ($value as u32)))*
+                __enumset_underlying: 0 $(| (1 << $crate::__internal::enum_bit(
+                    &[$value], $value as u128,
+                )))*
             },
         }.enum_set
     };
+    ($($value:path),+ $(,)?) => {
+        $crate::enum_set!($($value)|*)
+    };
+}
+
+/// Creates a EnumSet literal, rejecting duplicated variants at compile-time.
+///
+/// This works like [`enum_set!`], but fails to compile if any variant is listed more than once.
+/// This can catch copy-paste errors in large literals. Like [`enum_set!`], variants may be
+/// separated either by `|` or by commas.
+///
+/// # Examples
+///
+/// ```rust
+/// # use enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C }
+/// const CONST_SET: EnumSet<Enum> = enum_set_strict!(Enum::A | Enum::B);
+/// assert_eq!(CONST_SET, Enum::A | Enum::B);
+/// ```
+///
+/// Listing a variant twice is an error:
+///
+/// ```compile_fail
+/// # use wasmer_enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C }
+/// const CONST_SET: EnumSet<Enum> = enum_set_strict!(Enum::A | Enum::B | Enum::A);
+/// ```
+///
+/// ```compile_fail
+/// # use wasmer_enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C }
+/// const CONST_SET: EnumSet<Enum> = enum_set_strict!(Enum::A, Enum::B, Enum::A);
+/// ```
+#[macro_export]
+macro_rules! enum_set_strict {
+    () => {
+        $crate::enum_set!()
+    };
+    ($($value:path)|* $(|)*) => {{
+        const _: () = $crate::__internal::core_export::assert!(
+            (0u128 $(| (1u128 << $crate::__internal::enum_bit(&[$value], $value as u128)))*)
+                .count_ones() as usize ==
+                [$($crate::__internal::core_export::stringify!($value)),*].len(),
+            "enum_set_strict! contains duplicated variants",
+        );
+        $crate::enum_set!($($value)|*)
+    }};
+    ($($value:path),+ $(,)?) => {
+        $crate::enum_set_strict!($($value)|*)
+    };
+}
+
+/// Checks whether an EnumSet contains a variant. This can be used in const contexts.
+///
+/// The syntax used is `enum_set_contains!(SET, Type::A)`. The variant must be of the same type
+/// as the set, or a error will occur at compile-time.
+///
+/// # Examples
+///
+/// ```rust
+/// # use enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C }
+/// const CONST_SET: EnumSet<Enum> = enum_set!(Enum::A | Enum::B);
+/// const _: () = assert!(enum_set_contains!(CONST_SET, Enum::A));
+/// const _: () = assert!(!enum_set_contains!(CONST_SET, Enum::C));
+/// ```
+#[macro_export]
+macro_rules! enum_set_contains {
+    ($set:expr, $value:path $(,)?) => {
+        ($crate::__internal::EnumSetSameTypeHack {
+            unified: &[$value],
+            enum_set: $set,
+        }.enum_set.__enumset_underlying & (1 << $crate::__internal::enum_bit(
+            &[$value], $value as u128,
+        ))) != 0
+    };
+}
+
+/// Checks whether an EnumSet is empty. This can be used in const contexts.
+///
+/// # Examples
+///
+/// ```rust
+/// # use enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C }
+/// const EMPTY_SET: EnumSet<Enum> = enum_set!();
+/// const CONST_SET: EnumSet<Enum> = enum_set!(Enum::A | Enum::B);
+/// const _: () = assert!(enum_set_is_empty!(EMPTY_SET));
+/// const _: () = assert!(!enum_set_is_empty!(CONST_SET));
+/// ```
+///
+/// This macro is strongly typed. For example, the following will not compile:
+///
+/// ```compile_fail
+/// # use wasmer_enumset::*;
+/// struct NotASet { __enumset_underlying: u8 }
+/// let type_error = enum_set_is_empty!(NotASet { __enumset_underlying: 0 });
+/// ```
+#[macro_export]
+macro_rules! enum_set_is_empty {
+    ($set:expr $(,)?) => {{
+        let set: $crate::EnumSet<_> = $set;
+        set.__enumset_underlying == 0
+    }};
+}
+
+/// Checks whether an EnumSet is a subset of another, i.e., the second set contains at least all
+/// the values in the first. This can be used in const contexts.
+///
+/// The syntax used is `enum_set_is_subset!(SUBSET, SUPERSET)`. Both sets must be of the same
+/// type, or a error will occur at compile-time.
+///
+/// # Examples
+///
+/// ```rust
+/// # use enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C }
+/// const DEFAULTS: EnumSet<Enum> = enum_set!(Enum::A);
+/// const ALLOWED: EnumSet<Enum> = enum_set!(Enum::A | Enum::B);
+/// const _: () = assert!(enum_set_is_subset!(DEFAULTS, ALLOWED));
+/// const _: () = assert!(!enum_set_is_subset!(ALLOWED, DEFAULTS));
+/// ```
+///
+/// This macro is strongly typed. For example, the following will not compile:
+///
+/// ```compile_fail
+/// # use wasmer_enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C }
+/// # #[derive(EnumSetType, Debug)] enum Enum2 { A, B, C }
+/// const SET: EnumSet<Enum> = enum_set!(Enum::A);
+/// const SET2: EnumSet<Enum2> = enum_set!(Enum2::A);
+/// let type_error = enum_set_is_subset!(SET, SET2);
+/// ```
+#[macro_export]
+macro_rules! enum_set_is_subset {
+    ($subset:expr, $superset:expr $(,)?) => {{
+        let sets: [$crate::EnumSet<_>; 2] = [$subset, $superset];
+        (sets[0].__enumset_underlying & !sets[1].__enumset_underlying) == 0
+    }};
+}
+
+/// Checks whether two EnumSets are equal. This can be used in const contexts, where the
+/// [`PartialEq`] implementation cannot be used.
+///
+/// The syntax used is `enum_set_eq!(SET, SET2)`. Both sets must be of the same type, or a error
+/// will occur at compile-time.
+///
+/// # Examples
+///
+/// ```rust
+/// # use enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C }
+/// const CONST_SET: EnumSet<Enum> = enum_set!(Enum::A | Enum::B);
+/// const _: () = assert!(enum_set_eq!(CONST_SET, enum_set!(Enum::B | Enum::A)));
+/// const _: () = assert!(!enum_set_eq!(CONST_SET, enum_set!(Enum::A)));
+/// ```
+///
+/// This macro is strongly typed. For example, the following will not compile:
+///
+/// ```compile_fail
+/// # use wasmer_enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C }
+/// # #[derive(EnumSetType, Debug)] enum Enum2 { A, B, C }
+/// const SET: EnumSet<Enum> = enum_set!(Enum::A);
+/// const SET2: EnumSet<Enum2> = enum_set!(Enum2::A);
+/// let type_error = enum_set_eq!(SET, SET2);
+/// ```
+#[macro_export]
+macro_rules! enum_set_eq {
+    ($set:expr, $set2:expr $(,)?) => {{
+        let sets = [$set, $set2];
+        sets[0].__enumset_underlying == sets[1].__enumset_underlying
+    }};
+}
+
+/// Creates an EnumSet from an integer containing its bits. This can be used in const contexts,
+/// unlike [`EnumSet::from_u64`] and the other conversion functions.
+///
+/// The syntax used is `enum_set_from_bits!(Type, bits)`. If a bit that doesn't correspond to an
+/// enum variant is set, or `bits` is negative, this macro will panic, causing a compile-time
+/// error in const contexts.
+///
+/// This is a macro rather than a `const fn` on [`EnumSet`] because the conversion functions are
+/// generic over the underlying representation of the set, and converting integers to and from a
+/// generic type isn't possible in const functions on stable Rust. Naming the enum type lets the
+/// macro work with concrete types instead.
+///
+/// # Examples
+///
+/// ```rust
+/// # use enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C, D }
+/// const CONST_SET: EnumSet<Enum> = enum_set_from_bits!(Enum, 0b1011);
+/// assert_eq!(CONST_SET, Enum::A | Enum::B | Enum::D);
+/// ```
+///
+/// Setting an unknown bit is an error:
+///
+/// ```compile_fail
+/// # use wasmer_enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C, D }
+/// const CONST_SET: EnumSet<Enum> = enum_set_from_bits!(Enum, 0b10000);
+/// ```
+///
+/// Negative values are rejected rather than sign extended:
+///
+/// ```compile_fail
+/// # use wasmer_enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C, D }
+/// const CONST_SET: EnumSet<Enum> = enum_set_from_bits!(Enum, -1);
+/// ```
+#[macro_export]
+macro_rules! enum_set_from_bits {
+    ($enum_type:ty, $bits:expr $(,)?) => {{
+        let bits = $bits;
+        #[allow(unused_comparisons)]
+        let is_negative = bits < 0;
+        $crate::__internal::core_export::assert!(!is_negative, "Bitset must not be negative.");
+        let bits = bits as u128;
+        let all_bits = <$enum_type as $crate::__internal::EnumSetTypePrivate>::ALL_BITS as u128;
+        $crate::__internal::core_export::assert!(
+            bits & !all_bits == 0, "Bitset contains invalid variants.",
+        );
+        $crate::EnumSet::<$enum_type> { __enumset_underlying: bits as _ }
+    }};
+}
+
+/// Creates an EnumSet from an integer containing its bits, ignoring bits that don't correspond
+/// to an enum variant. This can be used in const contexts, unlike
+/// [`EnumSet::from_u64_truncated`] and the other conversion functions.
+///
+/// The syntax used is `enum_set_from_bits_truncated!(Type, bits)`. Like [`enum_set_from_bits!`],
+/// this macro will panic if `bits` is negative.
+///
+/// # Examples
+///
+/// ```rust
+/// # use enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C, D }
+/// const CONST_SET: EnumSet<Enum> = enum_set_from_bits_truncated!(Enum, 0b11011);
+/// assert_eq!(CONST_SET, Enum::A | Enum::B | Enum::D);
+/// ```
+#[macro_export]
+macro_rules! enum_set_from_bits_truncated {
+    ($enum_type:ty, $bits:expr $(,)?) => {{
+        let bits = $bits;
+        #[allow(unused_comparisons)]
+        let is_negative = bits < 0;
+        $crate::__internal::core_export::assert!(!is_negative, "Bitset must not be negative.");
+        let bits = bits as u128;
+        let all_bits = <$enum_type as $crate::__internal::EnumSetTypePrivate>::ALL_BITS as u128;
+        $crate::EnumSet::<$enum_type> { __enumset_underlying: (bits & all_bits) as _ }
+    }};
+}
+
+/// Asserts at compile time that an enum has exactly a given number of variants.
+///
+/// The syntax used is `const_assert_variant_count!(Type, count)`. This expands to an item, and
+/// can be used to lock down the variant count of an enum a serialized format depends on, so that
+/// adding or removing a variant is caught at build time.
+///
+/// # Examples
+///
+/// ```rust
+/// # use enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C, D }
+/// const_assert_variant_count!(Enum, 4);
+/// ```
+///
+/// A mismatched count is an error:
+///
+/// ```compile_fail
+/// # use wasmer_enumset::*;
+/// # #[derive(EnumSetType, Debug)] enum Enum { A, B, C, D }
+/// const_assert_variant_count!(Enum, 3);
+/// ```
+#[macro_export]
+macro_rules! const_assert_variant_count {
+    ($enum_type:ty, $count:expr $(,)?) => {
+        const _: () = $crate::__internal::core_export::assert!(
+            $crate::EnumSet::<$enum_type>::variant_count() == $count,
+            "enum does not have the expected number of variants",
+        );
+    };
 }
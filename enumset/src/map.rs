@@ -0,0 +1,145 @@
+use crate::*;
+
+/// A map type keyed by enum variants.
+///
+/// It is implemented using an [`EnumSet`] tracking which keys are present, alongside an array
+/// with one slot for each bit used by the enum's variants. Lookups index directly into that array
+/// using the same bit position an [`EnumSet`] would store the variant in.
+///
+/// # Examples
+///
+/// ```rust
+/// # use enumset::*;
+/// # #[derive(EnumSetType, Debug)] pub enum Enum { A, B, C, D, E, F, G }
+/// let mut map = EnumSetMap::new();
+/// map.insert(Enum::A, "a");
+/// map.insert(Enum::C, "c");
+/// assert_eq!(map.get(Enum::A), Some(&"a"));
+/// assert_eq!(map.get(Enum::B), None);
+/// assert_eq!(map.keys(), Enum::A | Enum::C);
+/// ```
+pub struct EnumSetMap<K: EnumSetType, V> {
+    keys: EnumSet<K>,
+    values: K::MapArray<V>,
+}
+impl <K: EnumSetType, V> EnumSetMap<K, V> {
+    /// Creates an empty `EnumSetMap`.
+    pub fn new() -> Self {
+        EnumSetMap { keys: EnumSet::new(), values: K::new_map_array() }
+    }
+
+    /// Returns the number of elements in this map.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+    /// Returns `true` if the map contains no elements.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+    /// Removes all elements from the map.
+    pub fn clear(&mut self) {
+        for slot in self.values.as_mut() {
+            *slot = None;
+        }
+        self.keys.clear();
+    }
+
+    /// Returns the set of keys present in this map.
+    pub fn keys(&self) -> EnumSet<K> {
+        self.keys
+    }
+    /// Checks whether this map contains a value for a key.
+    pub fn contains_key(&self, key: K) -> bool {
+        self.keys.contains(key)
+    }
+
+    /// Returns a reference to the value corresponding to a key.
+    pub fn get(&self, key: K) -> Option<&V> {
+        self.values.as_ref()[key.enum_into_u32() as usize].as_ref()
+    }
+    /// Returns a mutable reference to the value corresponding to a key.
+    pub fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        self.values.as_mut()[key.enum_into_u32() as usize].as_mut()
+    }
+
+    /// Inserts a value into this map.
+    ///
+    /// If the map already had a value present for this key, the old value is returned.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.keys.insert(key);
+        self.values.as_mut()[key.enum_into_u32() as usize].replace(value)
+    }
+    /// Removes a key from this map, returning the value at the key if it was present.
+    pub fn remove(&mut self, key: K) -> Option<V> {
+        self.keys.remove(key);
+        self.values.as_mut()[key.enum_into_u32() as usize].take()
+    }
+
+    /// Creates an iterator over the keys and values in this map, in the same order an
+    /// [`EnumSetIter`] would visit the keys.
+    pub fn iter(&self) -> EnumSetMapIter<'_, K, V> {
+        EnumSetMapIter { keys: self.keys.iter(), values: self.values.as_ref() }
+    }
+}
+
+impl <K: EnumSetType, V> Default for EnumSetMap<K, V> {
+    /// Returns an empty map.
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl <K: EnumSetType, V: Clone> Clone for EnumSetMap<K, V> {
+    fn clone(&self) -> Self {
+        let mut map = EnumSetMap::new();
+        for (key, value) in self.iter() {
+            map.insert(key, value.clone());
+        }
+        map
+    }
+}
+
+impl <K: EnumSetType + Debug, V: Debug> Debug for EnumSetMap<K, V> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+impl <K: EnumSetType, V> FromIterator<(K, V)> for EnumSetMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map = EnumSetMap::new();
+        iter.into_iter().for_each(|(k, v)| { map.insert(k, v); });
+        map
+    }
+}
+
+impl <'a, K: EnumSetType, V> IntoIterator for &'a EnumSetMap<K, V> {
+    type Item = (K, &'a V);
+    type IntoIter = EnumSetMapIter<'a, K, V>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// The iterator used by [`EnumSetMap`]s.
+pub struct EnumSetMapIter<'a, K: EnumSetType, V> {
+    keys: EnumSetIter<K>,
+    values: &'a [Option<V>],
+}
+impl <'a, K: EnumSetType, V> Iterator for EnumSetMapIter<'a, K, V> {
+    type Item = (K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let values = self.values;
+        self.keys.next().map(|key| {
+            let value = values[key.enum_into_u32() as usize].as_ref();
+            (key, value.expect("EnumSetMap keys and values are out of sync."))
+        })
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.keys.size_hint()
+    }
+}
+
+impl <K: EnumSetType, V> ExactSizeIterator for EnumSetMapIter<'_, K, V> {}
@@ -0,0 +1,47 @@
+use bitflags::bitflags;
+use enumset::*;
+use std::convert::TryFrom;
+
+#[derive(EnumSetType, Debug)]
+#[enumset(repr = "u16")]
+pub enum Flag {
+    A, B, C, D = 8,
+}
+
+bitflags! {
+    pub struct Flags: u16 {
+        const A = 1 << 0;
+        const B = 1 << 1;
+        const C = 1 << 2;
+        const D = 1 << 8;
+        const UNKNOWN = 1 << 15;
+    }
+}
+
+enum_set_bitflags!(Flag, Flags);
+
+#[test]
+fn to_bitflags() {
+    assert_eq!(Flags::from(Flag::A | Flag::D), Flags::A | Flags::D);
+    assert_eq!(Flags::from(EnumSet::<Flag>::empty()), Flags::empty());
+    assert_eq!(Flags::from(EnumSet::<Flag>::all()), Flags::all() - Flags::UNKNOWN);
+}
+
+#[test]
+fn from_bitflags() {
+    assert_eq!(EnumSet::from(Flags::B | Flags::C), Flag::B | Flag::C);
+    assert_eq!(EnumSet::<Flag>::from(Flags::all()), EnumSet::all());
+    assert_eq!(EnumSet::<Flag>::from(Flags::UNKNOWN), EnumSet::empty());
+}
+
+#[test]
+fn from_bitflags_checked() {
+    let bits = (Flags::A | Flags::D).bits() as u128;
+    assert_eq!(EnumSet::<Flag>::try_from(bits), Ok(Flag::A | Flag::D));
+    let error = EnumSet::<Flag>::try_from(Flags::all().bits() as u128).unwrap_err();
+    assert_eq!(error.unknown_bits(), Flags::UNKNOWN.bits() as u128);
+    assert_eq!(EnumSet::<Flag>::try_from(1u128 << 100).unwrap_err().unknown_bits(), 1 << 100);
+    assert_eq!(EnumSet::<Flag>::from_raw_bits_checked(bits), Ok(Flag::A | Flag::D));
+    let invalid = EnumSet::<Flag>::from_raw_bits_checked(Flags::all().bits() as u128);
+    assert_eq!(invalid, Err(Flags::UNKNOWN.bits() as u128));
+}
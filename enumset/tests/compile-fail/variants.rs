@@ -27,6 +27,11 @@ enum NegativeVariant {
     Variant = -1,
 }
 
+#[derive(EnumSetType)]
+enum ShiftVariant {
+    Variant = 1 << 1,
+}
+
 #[derive(EnumSetType)]
 #[repr(usize)]
 enum BadRepr {
@@ -68,6 +68,157 @@ pub enum ReprEnum3 {
 pub enum ReprEnum4 {
     A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R, S, T, U, V, W, X, Y, Z,
 }
+#[derive(EnumSetType, Debug)]
+#[enumset(repr = "u32")]
+pub enum ReprOverrideEnum {
+    A, B, C, D, E, F, G, H,
+}
+#[derive(EnumSetType, Debug)]
+#[enumset(with_name)]
+pub enum NamedEnum {
+    A, B, C, r#Type,
+}
+
+#[derive(EnumSetType, Debug)]
+#[enumset(with_name)]
+pub enum NamedEmptyEnum { }
+
+#[test]
+fn iter_names() {
+    const NAME: &str = NamedEnum::B.name();
+    assert_eq!(NAME, "B");
+    assert_eq!(NamedEnum::r#Type.name(), "Type");
+    let set = NamedEnum::A | NamedEnum::C | NamedEnum::r#Type;
+    assert_eq!(set.iter_names().len(), 3);
+    assert_eq!(set.iter_names().collect::<Vec<_>>(), vec!["A", "C", "Type"]);
+    assert_eq!(EnumSet::<NamedEmptyEnum>::all().iter_names().count(), 0);
+    assert_eq!(set.iter_named().len(), 3);
+    assert_eq!(set.iter_named().collect::<Vec<_>>(),
+               vec![(NamedEnum::A, "A"), (NamedEnum::C, "C"), (NamedEnum::r#Type, "Type")]);
+}
+#[derive(EnumSetType, Debug)]
+#[enumset(with_mask)]
+pub enum VariantMaskEnum {
+    A, B, C = 10, D = 100,
+}
+
+#[derive(EnumSetType, Debug)]
+#[enumset(with_mask, discriminant_is_mask)]
+pub enum VariantMaskIsMaskEnum {
+    A = 1, B = 4, C = 64,
+}
+
+#[derive(EnumSetType, Debug)]
+#[enumset(with_mask)]
+pub enum VariantMaskEmptyEnum { }
+
+#[test]
+fn variant_masks() {
+    const AC: u128 = VariantMaskEnum::A.mask() | VariantMaskEnum::C.mask();
+    assert_eq!(AC, 0b100_0000_0001);
+    assert_eq!(VariantMaskEnum::D.mask(), 1 << 100);
+    for v in EnumSet::<VariantMaskEnum>::all() {
+        assert_eq!(v.mask(), EnumSet::only(v).as_u128());
+    }
+    assert_eq!(VariantMaskIsMaskEnum::A.mask(), 1);
+    assert_eq!(VariantMaskIsMaskEnum::B.mask(), 4);
+    assert_eq!(VariantMaskIsMaskEnum::C.mask(), 64);
+    for v in EnumSet::<VariantMaskIsMaskEnum>::all() {
+        assert_eq!(v.mask(), EnumSet::only(v).as_u128());
+    }
+    assert_eq!(EnumSet::<VariantMaskEmptyEnum>::all().iter().count(), 0);
+}
+#[derive(EnumSetType, Debug)]
+pub enum CfgEnum {
+    A = 0,
+    #[cfg(any())] B = 1,
+    C = 2,
+    D = 3,
+    #[cfg(any())] E = 4,
+}
+#[derive(EnumSetType, Debug)]
+pub enum ImplicitCfgEnum {
+    A,
+    #[cfg(any())] B,
+    C,
+}
+
+#[test]
+fn cfg_variants() {
+    assert_eq!(EnumSet::<CfgEnum>::variant_count(), 3);
+    assert_eq!(EnumSet::<CfgEnum>::all().as_u8(), 0b1101);
+    assert_eq!((CfgEnum::C | CfgEnum::D).as_u8(), 0b1100);
+    assert_eq!(EnumSet::<ImplicitCfgEnum>::all().as_u8(), 0b11);
+    assert_eq!(EnumSet::only(ImplicitCfgEnum::C).as_u8(), 0b10);
+}
+
+const_assert_variant_count!(EmptyEnum, 0);
+const_assert_variant_count!(SmallEnum, 26);
+const_assert_variant_count!(SparseEnum, 8);
+const_assert_variant_count!(CfgEnum, 3);
+
+#[derive(EnumSetType, Debug)]
+#[enumset(discriminant_is_mask)]
+pub enum MaskEnum {
+    A = 1 << 0, B = 1 << 1, C = 4, D = 1 << 3, E = 0x10, F = (1 << 5), G = 1 << 6, H = 1 << 7,
+}
+
+#[repr(u128)]
+#[derive(EnumSetType, Debug)]
+#[enumset(discriminant_is_mask)]
+pub enum LargeMaskEnum {
+    A = 1, B, C = 4, D = 1 << 32, E = 1 << 33, F = 1 << 64, G = 1 << 65, H = 1 << 127,
+}
+
+#[test]
+fn mask_discriminants() {
+    assert_eq!((MaskEnum::A | MaskEnum::C | MaskEnum::E).as_u8(), 0b10101);
+    assert_eq!(EnumSet::<MaskEnum>::all().as_u8(), 0xFF);
+    assert_eq!(EnumSet::<LargeMaskEnum>::all().as_u128(),
+               0b111 | (0b11 << 32) | (0b11 << 64) | (1 << 127));
+    assert_eq!(EnumSet::<LargeMaskEnum>::bit_width(), 128);
+    assert_ne!(LargeMaskEnum::D, LargeMaskEnum::E);
+    assert_eq!(EnumSet::<LargeMaskEnum>::variant_at(33), Some(LargeMaskEnum::E));
+}
+
+mod outer {
+    pub mod inner {
+        // Shadow prelude items and standard macros to check the custom derive doesn't rely on
+        // them being in scope.
+        #[allow(unused_macros)]
+        macro_rules! panic { () => {} }
+        #[allow(unused_macros)]
+        macro_rules! concat { () => {} }
+        pub trait Into { }
+        pub struct Option;
+
+        #[derive(enumset::EnumSetType, Debug)]
+        #[enumset(with_name, with_mask)]
+        pub enum NestedEnum {
+            A, B, C,
+        }
+
+        #[derive(enumset::EnumSetType, Debug)]
+        pub enum NestedEmptyEnum { }
+    }
+}
+
+#[test]
+fn nested_enums() {
+    use outer::inner::NestedEnum;
+
+    #[derive(EnumSetType, Debug)]
+    enum LocalEnum {
+        A, B, C,
+    }
+
+    let set = NestedEnum::A | NestedEnum::C;
+    assert_eq!(set.as_u8(), 0b101);
+    assert_eq!(set.iter_names().collect::<Vec<_>>(), vec!["A", "C"]);
+    assert_eq!(NestedEnum::C.mask(), 0b100);
+    assert_eq!((LocalEnum::A | LocalEnum::C) - LocalEnum::A, LocalEnum::C);
+    assert_eq!(EnumSet::<outer::inner::NestedEmptyEnum>::all().len(), 0);
+}
 
 macro_rules! test_variants {
     ($enum_name:ident $all_empty_test:ident $($variant:ident,)*) => {
@@ -113,6 +264,76 @@ macro_rules! test_enum {
             assert!(EMPTY_SET.is_empty());
         }
 
+        #[test]
+        fn const_set_commas() {
+            const COMMA_SET: EnumSet<$e> = enum_set!($e::A, $e::C);
+            const TRAILING_COMMA_SET: EnumSet<$e> = enum_set!($e::C,);
+            assert_eq!(COMMA_SET, CONST_SET);
+            assert_eq!(TRAILING_COMMA_SET, $e::C);
+            assert_eq!(enum_set!($e::A, $e::B, $e::C, $e::A), $e::A | $e::B | $e::C);
+        }
+
+        #[test]
+        fn const_set_variable() {
+            const fn only(value: $e) -> EnumSet<$e> {
+                enum_set!(value)
+            }
+            const SINGLETONS: [EnumSet<$e>; 2] = [only($e::A), only($e::C)];
+            assert_eq!(SINGLETONS, [EnumSet::only($e::A), EnumSet::only($e::C)]);
+            for value in EnumSet::<$e>::all() {
+                assert_eq!(only(value), EnumSet::only(value));
+            }
+        }
+
+        #[test]
+        fn const_set_strict() {
+            const STRICT_SET: EnumSet<$e> = enum_set_strict!($e::A | $e::C);
+            const STRICT_EMPTY_SET: EnumSet<$e> = enum_set_strict!();
+            assert_eq!(STRICT_SET, CONST_SET);
+            assert_eq!(STRICT_EMPTY_SET, EMPTY_SET);
+            assert_eq!(enum_set_strict!($e::A | $e::B | $e::C), $e::A | $e::B | $e::C);
+            const COMMA_SET: EnumSet<$e> = enum_set_strict!($e::A, $e::C,);
+            assert_eq!(COMMA_SET, CONST_SET);
+            assert_eq!(enum_set_strict!($e::B), $e::B);
+        }
+
+        #[test]
+        fn const_from_bits() {
+            const FROM_BITS: EnumSet<$e> = enum_set_from_bits!($e, CONST_SET.__enumset_underlying);
+            const TRUNCATED: EnumSet<$e> = enum_set_from_bits_truncated!($e, !0u128);
+            assert_eq!(FROM_BITS, CONST_SET);
+            assert_eq!(TRUNCATED, EnumSet::all());
+            assert_eq!(enum_set_from_bits!($e, 0u8), EMPTY_SET);
+        }
+
+        #[test]
+        fn const_queries() {
+            const SUBSET: EnumSet<$e> = enum_set!($e::A);
+            const _: () = assert!(enum_set_contains!(CONST_SET, $e::A));
+            const _: () = assert!(!enum_set_contains!(CONST_SET, $e::B));
+            const _: () = assert!(enum_set_is_empty!(EMPTY_SET));
+            const _: () = assert!(!enum_set_is_empty!(CONST_SET));
+            const _: () = assert!(enum_set_is_subset!(SUBSET, CONST_SET));
+            const _: () = assert!(enum_set_is_subset!(EMPTY_SET, CONST_SET));
+            const _: () = assert!(!enum_set_is_subset!(CONST_SET, SUBSET));
+            const _: () = assert!(enum_set_eq!(CONST_SET, enum_set!($e::C | $e::A)));
+            const _: () = assert!(!enum_set_eq!(CONST_SET, SUBSET));
+            let set = $e::A | $e::C;
+            assert!(enum_set_contains!(set, $e::C));
+            assert!(enum_set_is_subset!(set, set));
+            assert!(!enum_set_is_empty!(set));
+        }
+
+        #[test]
+        fn const_eq() {
+            const _: () = assert!(CONST_SET.const_eq(enum_set!($e::C | $e::A)));
+            const _: () = assert!(!CONST_SET.const_eq(EMPTY_SET));
+            let all = EnumSet::<$e>::all();
+            assert!(all.const_eq(all));
+            assert!(!all.const_eq(all - $e::C));
+            assert!(EMPTY_SET.const_eq(all - all));
+        }
+
         #[test]
         fn basic_add_remove() {
             let mut set = EnumSet::new();
@@ -131,6 +352,118 @@ macro_rules! test_enum {
             assert!(!set.is_empty());
             set.clear();
             assert!(set.is_empty());
+            set.fill();
+            assert!(set.is_all());
+        }
+
+        #[test]
+        fn with_without_test() {
+            let set = EnumSet::new().with($e::A).with($e::C).with($e::A);
+            assert_eq!(set, $e::A | $e::C);
+            assert_eq!(set.without($e::A), $e::C);
+            assert_eq!(set.without($e::B), set);
+        }
+
+        #[test]
+        fn insert_iter_test() {
+            let mut set = $e::A | $e::B;
+            assert_eq!(set.insert_iter(vec![$e::B, $e::C, $e::D, $e::C]), 2);
+            assert_eq!(set, $e::A | $e::B | $e::C | $e::D);
+            assert_eq!(set.insert_iter(vec![$e::A, $e::D]), 0);
+            assert_eq!(set.insert_iter(Vec::new()), 0);
+        }
+
+        #[test]
+        fn apply_test() {
+            let mut set = $e::A | $e::B | $e::C;
+            set.apply($e::B | $e::C, $e::C | $e::D);
+            assert_eq!(set, $e::A | $e::C | $e::D);
+            set.apply(EMPTY_SET, EMPTY_SET);
+            assert_eq!(set, $e::A | $e::C | $e::D);
+            set.apply(EnumSet::all(), $e::E.into());
+            assert_eq!(set, $e::E);
+        }
+
+        #[test]
+        fn changed_test() {
+            let mut set = $e::A | $e::B;
+            assert!(set.insert_all_changed($e::B | $e::C));
+            assert!(!set.insert_all_changed($e::A | $e::C));
+            assert_eq!(set, $e::A | $e::B | $e::C);
+            assert!(!set.remove_all_changed($e::D | $e::E));
+            assert!(set.remove_all_changed($e::A | $e::E));
+            assert_eq!(set, $e::B | $e::C);
+        }
+
+        #[test]
+        fn clamp_test() {
+            let allowed = $e::A | $e::B | $e::C;
+            let mut set = $e::B | $e::C | $e::D;
+            assert_eq!(set.clamped(allowed), $e::B | $e::C);
+            assert_eq!(set, $e::B | $e::C | $e::D);
+            set.clamp_to(allowed);
+            assert_eq!(set, $e::B | $e::C);
+            set.clamp_to(EnumSet::empty());
+            assert!(set.is_empty());
+        }
+
+        #[test]
+        fn reverse_rotate_test() {
+            let width = EnumSet::<$e>::bit_width();
+            let set = $e::A | $e::C | $e::D;
+            for (idx, _) in set.reverse_bits_within_width().iter_indexed() {
+                assert!(set.contains(EnumSet::<$e>::variant_at(width - 1 - idx).unwrap()));
+            }
+            let all = EnumSet::<$e>::all();
+            if all.reverse_bits_within_width() == all {
+                assert_eq!(set.reverse_bits_within_width().reverse_bits_within_width(), set);
+            }
+            for n in 0..width + 2 {
+                for (idx, _) in set.rotate_left(n).iter_indexed() {
+                    let from = (idx + width - n % width) % width;
+                    assert!(set.contains(EnumSet::<$e>::variant_at(from).unwrap()));
+                }
+                if all.rotate_left(n) == all {
+                    assert_eq!(set.rotate_left(n).rotate_right(n), set);
+                }
+            }
+            assert_eq!(set.rotate_left(width), set);
+            assert_eq!(set.rotate_right(0), set);
+        }
+
+        #[test]
+        fn shift_above_test() {
+            let set = $e::A | $e::C | $e::D;
+            assert_eq!(set.shift_above(0, 0), set);
+            assert_eq!(set.shift_above(200, 5), set);
+            assert_eq!(set.try_shift_above(0, 200), None);
+            assert_eq!(set.try_shift_above(0, -200), None);
+            assert_eq!(set.shift_above_truncated(0, 200), EMPTY_SET);
+            assert_eq!(set.shift_above_truncated(0, i32::MIN), EMPTY_SET);
+            let pivot = EnumSet::<$e>::index_of($e::C);
+            let shifted = set.shift_above_truncated(pivot, 1);
+            assert!(shifted.contains($e::A));
+            for v in shifted - $e::A {
+                let idx = EnumSet::<$e>::index_of(v);
+                assert!(set.contains(EnumSet::<$e>::variant_at(idx - 1).unwrap()));
+            }
+            if let Some(shifted) = set.try_shift_above(pivot, 1) {
+                assert_eq!(shifted.shift_above(pivot + 1, -1), set);
+            }
+        }
+
+        #[test]
+        fn truncate_test() {
+            let mut set = $e::A | $e::C | $e::D | $e::E;
+            set.truncate(5);
+            assert_eq!(set, $e::A | $e::C | $e::D | $e::E);
+            set.truncate(2);
+            assert_eq!(set, $e::A | $e::C);
+            set.truncate(0);
+            assert!(set.is_empty());
+            let mut all = EnumSet::<$e>::all();
+            all.truncate(3);
+            assert_eq!(all, EnumSet::<$e>::all().iter().take(3).collect::<EnumSet<_>>());
         }
 
         #[test]
@@ -147,11 +480,58 @@ macro_rules! test_enum {
             assert_eq!(EnumSet::<$e>::empty().len(), 0)
         }
 
+        #[test]
+        fn count_test() {
+            assert_eq!(EnumSet::<$e>::empty().count(), 0);
+            assert_eq!(($e::A | $e::C | $e::E).count(), 3);
+            assert_eq!(EnumSet::<$e>::all().count(), EnumSet::<$e>::variant_count());
+        }
+
+        #[test]
+        fn all_is_all() {
+            assert!(EnumSet::<$e>::all().is_all());
+            assert!(!EnumSet::<$e>::empty().is_all());
+            assert!(!(EnumSet::<$e>::all() - $e::A).is_all());
+        }
+
+        #[test]
+        fn leading_trailing_absent_test() {
+            let width = EnumSet::<$e>::bit_width();
+            assert_eq!(EMPTY_SET.leading_absent(), width);
+            assert_eq!(EMPTY_SET.trailing_absent(), width);
+            assert_eq!(EnumSet::<$e>::all().leading_absent(), 0);
+            let set = $e::B | $e::D;
+            let bits = set.as_u128();
+            assert_eq!(set.trailing_absent(), bits.trailing_zeros());
+            assert_eq!(set.leading_absent(), width - (128 - bits.leading_zeros()));
+            let first = EnumSet::<$e>::all().as_u128().trailing_zeros();
+            assert_eq!(EnumSet::<$e>::all().trailing_absent(), first);
+        }
+
+        #[test]
+        fn is_singleton_test() {
+            assert!(!EMPTY_SET.is_singleton());
+            assert!(EnumSet::only($e::A).is_singleton());
+            assert!(EnumSet::only($e::E).is_singleton());
+            assert!(!($e::A | $e::C).is_singleton());
+            assert_eq!(EnumSet::<$e>::all().is_singleton(), EnumSet::<$e>::variant_count() == 1);
+        }
+
         #[test]
         fn all_len() {
             assert_eq!(EnumSet::<$e>::all().len(), EnumSet::<$e>::variant_count() as usize)
         }
 
+        #[test]
+        fn const_counts() {
+            const VARIANT_COUNT: u32 = EnumSet::<$e>::variant_count();
+            const BIT_WIDTH: u32 = EnumSet::<$e>::bit_width();
+            let per_variant = [0u8; EnumSet::<$e>::variant_count() as usize];
+            assert_eq!(per_variant.len(), EnumSet::<$e>::all().len());
+            assert_eq!(VARIANT_COUNT, EnumSet::<$e>::all().as_u128().count_ones());
+            assert_eq!(BIT_WIDTH, 128 - EnumSet::<$e>::all().as_u128().leading_zeros());
+        }
+
         #[test]
         fn iter_test() {
             let mut set = EnumSet::new();
@@ -191,6 +571,357 @@ macro_rules! test_enum {
             assert_eq!(set, set_5);
         }
 
+        #[test]
+        fn iter_rev_test() {
+            let set = $e::A | $e::C | $e::E;
+            assert_eq!(set.iter_rev().collect::<Vec<_>>(), vec![$e::E, $e::C, $e::A]);
+            assert_eq!(set.iter_rev().len(), 3);
+            let all: Vec<_> = EnumSet::<$e>::all().iter_rev().collect();
+            let mut forward: Vec<_> = EnumSet::<$e>::all().iter().collect();
+            forward.reverse();
+            assert_eq!(all, forward);
+            assert_eq!(EMPTY_SET.iter_rev().next(), None);
+        }
+
+        #[test]
+        fn iter_peek_test() {
+            let set = $e::A | $e::C | $e::E;
+            let mut iter = set.iter();
+            while let Some(peeked) = iter.peek() {
+                assert_eq!(iter.peek(), Some(peeked));
+                assert_eq!(iter.next(), Some(peeked));
+            }
+            assert_eq!(iter.next(), None);
+            assert_eq!(EnumSet::<$e>::empty().iter().peek(), None);
+        }
+
+        #[cfg(feature = "alloc")]
+        #[test]
+        fn to_vec_test() {
+            let set = $e::E | $e::A | $e::C;
+            assert_eq!(set.to_vec(), vec![$e::A, $e::C, $e::E]);
+            assert!(EnumSet::<$e>::empty().to_vec().is_empty());
+        }
+
+        #[cfg(feature = "alloc")]
+        #[test]
+        fn category_counts_test() {
+            let sets = vec![$e::A | $e::B, $e::C.into(), $e::A | $e::D, EMPTY_SET];
+            let categories = [$e::A | $e::C, EnumSet::only($e::D), EMPTY_SET, EnumSet::all()];
+            assert_eq!(category_counts(sets.iter().copied(), &categories), vec![3, 1, 0, 3]);
+            assert_eq!(category_counts(sets, &[]), Vec::<usize>::new());
+            assert_eq!(category_counts(Vec::new(), &categories), vec![0; 4]);
+        }
+
+        #[cfg(feature = "alloc")]
+        #[test]
+        fn json_indices_test() {
+            let set = $e::E | $e::A | $e::C;
+            let json = set.to_json_indices();
+            assert_eq!(EnumSet::from_json_indices(&json), Some(set));
+            assert_eq!(EnumSet::<$e>::empty().to_json_indices(), "[]");
+            let all = EnumSet::<$e>::all().to_json_indices();
+            assert_eq!(EnumSet::from_json_indices(&all), Some(EnumSet::<$e>::all()));
+        }
+
+        #[test]
+        fn from_json_indices_test() {
+            let json = format!(" [ {} ,{}] ", EnumSet::<$e>::index_of($e::A),
+                               EnumSet::<$e>::index_of($e::C));
+            assert_eq!(EnumSet::from_json_indices(&json), Some($e::A | $e::C));
+            assert_eq!(EnumSet::<$e>::from_json_indices("[ ]"), Some(EMPTY_SET));
+            assert_eq!(EnumSet::<$e>::from_json_indices("[200]"), None);
+            assert_eq!(EnumSet::<$e>::from_json_indices("[-1]"), None);
+            assert_eq!(EnumSet::<$e>::from_json_indices("[+1]"), None);
+            assert_eq!(EnumSet::<$e>::from_json_indices("[1,]"), None);
+            assert_eq!(EnumSet::<$e>::from_json_indices("1"), None);
+            assert_eq!(EnumSet::<$e>::from_json_indices("[99999999999]"), None);
+        }
+
+        #[cfg(feature = "arrayvec")]
+        #[test]
+        fn to_arrayvec_test() {
+            let set = $e::E | $e::A | $e::C;
+            let vec = set.to_arrayvec::<128>();
+            assert_eq!(vec.as_slice(), [$e::A, $e::C, $e::E]);
+            assert!(EnumSet::<$e>::empty().to_arrayvec::<128>().is_empty());
+            assert_eq!(EnumSet::<$e>::all().to_arrayvec::<128>().len(),
+                       EnumSet::<$e>::variant_count() as usize);
+        }
+
+        #[cfg(feature = "fixedbitset")]
+        #[test]
+        fn fixedbitset_test() {
+            let set = $e::E | $e::A | $e::C;
+            let bitset = set.to_fixedbitset();
+            assert_eq!(bitset.len(), EnumSet::<$e>::bit_width() as usize);
+            assert!(bitset.ones().map(|bit| bit as u32).eq(set.iter_bits()));
+            assert_eq!(EnumSet::try_from_fixedbitset(&bitset), Some(set));
+            assert_eq!(EnumSet::try_from_fixedbitset(&EMPTY_SET.to_fixedbitset()), Some(EMPTY_SET));
+
+            let mut invalid = fixedbitset::FixedBitSet::with_capacity(200);
+            invalid.insert(150);
+            assert_eq!(EnumSet::<$e>::try_from_fixedbitset(&invalid), None);
+        }
+
+        #[test]
+        fn iter_ranges_test() {
+            let set = $e::A | $e::B | $e::C | $e::E | $e::G;
+            let ranges: Vec<_> = set.iter_ranges().collect();
+            for &(start, end) in &ranges {
+                let run = EnumSet::from_range(start..=end);
+                assert!(set.is_superset(run));
+            }
+            let union: EnumSet<_> = ranges.iter().map(|&(s, e)| EnumSet::from_range(s..=e)).sum();
+            assert_eq!(union, set);
+            assert_eq!(EnumSet::<$e>::empty().iter_ranges().count(), 0);
+            let all: Vec<_> = EnumSet::<$e>::all().iter_ranges().collect();
+            assert_eq!(all.iter().map(|&(s, e)| EnumSet::from_range(s..=e)).sum::<EnumSet<_>>(),
+                       EnumSet::all());
+        }
+
+        #[test]
+        fn power_set_test() {
+            let mut iter = EnumSet::<$e>::power_set();
+            assert_eq!(iter.next(), Some(EnumSet::empty()));
+            let mut last = EnumSet::<$e>::empty();
+            for set in iter.take(1000) {
+                assert!(set.as_u128() > last.as_u128());
+                assert_eq!(EnumSet::<$e>::try_from_u128(set.as_u128()), Some(set));
+                last = set;
+            }
+            if EnumSet::<$e>::variant_count() <= 8 {
+                let count = EnumSet::<$e>::power_set().count();
+                assert_eq!(count, 1 << EnumSet::<$e>::variant_count());
+                assert_eq!(count as u128, EnumSet::<$e>::SUBSET_COUNT);
+                assert_eq!(EnumSet::<$e>::power_set().last(), Some(EnumSet::all()));
+            }
+        }
+
+        #[test]
+        fn dense_index_test() {
+            for set in EnumSet::<$e>::power_set().take(1000) {
+                let index = set.dense_index();
+                assert!(index < EnumSet::<$e>::SUBSET_COUNT ||
+                        EnumSet::<$e>::SUBSET_COUNT == u128::MAX);
+                assert_eq!(EnumSet::<$e>::from_dense_index(index), set);
+            }
+            assert_eq!(EnumSet::<$e>::empty().dense_index(), 0);
+            let all_index = EnumSet::<$e>::all().dense_index();
+            assert_eq!(all_index.count_ones(), EnumSet::<$e>::variant_count());
+            assert_eq!(all_index.trailing_ones(), EnumSet::<$e>::variant_count());
+        }
+
+        #[test]
+        fn mask_below_test() {
+            for n in 0..200 {
+                let below = EnumSet::<$e>::mask_below(n);
+                let from = EnumSet::<$e>::mask_from(n);
+                assert!(below.is_disjoint(from));
+                assert_eq!(below | from, EnumSet::all());
+                for (idx, _) in below.iter_indexed() {
+                    assert!(idx < n);
+                }
+                for (idx, _) in from.iter_indexed() {
+                    assert!(idx >= n);
+                }
+            }
+            assert_eq!(EnumSet::<$e>::mask_below(0), EnumSet::empty());
+            assert_eq!(EnumSet::<$e>::mask_from(0), EnumSet::all());
+            assert_eq!(EnumSet::<$e>::mask_below(!0), EnumSet::all());
+            assert_eq!(EnumSet::<$e>::mask_from(!0), EnumSet::empty());
+        }
+
+        #[test]
+        fn chunks_test() {
+            let set = $e::A | $e::C | $e::E | $e::G;
+            for size in 1..10 {
+                let chunks: Vec<_> = set.chunks(size).collect();
+                let width = EnumSet::<$e>::bit_width();
+                assert_eq!(chunks.len() as u32, width / size + (width % size != 0) as u32);
+                assert_eq!(set.chunks(size).len(), chunks.len());
+                for (k, chunk) in chunks.iter().enumerate() {
+                    for (idx, _) in chunk.iter_indexed() {
+                        assert_eq!(idx / size, k as u32);
+                    }
+                }
+                assert_eq!(chunks.iter().sum::<EnumSet<_>>(), set);
+            }
+            assert_eq!(set.chunks(!0).collect::<Vec<_>>(), vec![set]);
+        }
+
+        #[test]
+        fn index_of_test() {
+            for (idx, v) in EnumSet::<$e>::all().iter_indexed() {
+                assert_eq!(EnumSet::<$e>::index_of(v), idx);
+                assert_eq!(EnumSet::<$e>::variant_at(idx), Some(v));
+            }
+            let width = EnumSet::<$e>::bit_width();
+            let invalid = (0..200).filter(|&idx| EnumSet::<$e>::variant_at(idx).is_none());
+            assert_eq!(invalid.count(), 200 - EnumSet::<$e>::variant_count() as usize);
+            assert_eq!(EnumSet::<$e>::variant_at(width), None);
+        }
+
+        #[test]
+        fn contains_index_test() {
+            let set = $e::A | $e::C;
+            for bit in 0..200 {
+                let expected = EnumSet::<$e>::variant_at(bit).map_or(false, |v| set.contains(v));
+                assert_eq!(set.contains_index(bit), expected);
+            }
+            assert!(!set.contains_index(!0));
+        }
+
+        #[test]
+        fn iter_bits_test() {
+            let set = $e::A | $e::C | $e::E;
+            let bits: Vec<_> = set.iter_bits().collect();
+            assert_eq!(bits, set.iter_indexed().map(|(idx, _)| idx).collect::<Vec<_>>());
+            assert_eq!(set.iter_bits().len(), 3);
+            let raw = EnumSet::<$e> { __enumset_underlying: !0 };
+            assert_eq!(raw.iter_bits().count(), EnumSet::<$e>::REPR_SIZE_BYTES * 8);
+            assert_eq!(raw.iter_bits().last(), Some(EnumSet::<$e>::REPR_SIZE_BYTES as u32 * 8 - 1));
+        }
+
+        #[test]
+        fn singletons_test() {
+            let set = $e::A | $e::C | $e::E;
+            let singletons: Vec<_> = set.singletons().collect();
+            assert_eq!(singletons, vec![EnumSet::only($e::A), $e::C.into(), $e::E.into()]);
+            assert_eq!(set.singletons().len(), 3);
+            assert_eq!(set.singletons().sum::<EnumSet<_>>(), set);
+            assert_eq!(EMPTY_SET.singletons().count(), 0);
+        }
+
+        #[test]
+        fn project_test() {
+            let set = $e::A | $e::C | $e::D;
+            let projected = set.project(|v| match v {
+                $e::A => Some(Enum8::A),
+                $e::C | $e::D => Some(Enum8::B),
+                _ => None,
+            });
+            assert_eq!(projected, Enum8::A | Enum8::B);
+            assert_eq!(set.project(|_| None::<Enum8>), EnumSet::empty());
+            assert_eq!(EMPTY_SET.project(|_| Some(Enum8::H)), EnumSet::empty());
+            assert_eq!(set.project(Some), set);
+        }
+
+        #[test]
+        fn min_max_by_key_test() {
+            let set = $e::A | $e::C | $e::E;
+            let key = |v: $e| if v == $e::C { 0 } else { 1 };
+            assert_eq!(set.min_by_key(key), Some($e::C));
+            assert_eq!(set.max_by_key(key), Some($e::E));
+            assert_eq!(set.min_by_key(|_| 0u8), Some($e::A));
+            assert_eq!(set.max_by_key(|_| 0u8), Some($e::E));
+            assert_eq!(EMPTY_SET.min_by_key(key), None);
+            assert_eq!(EMPTY_SET.max_by_key(key), None);
+        }
+
+        #[test]
+        fn words_test() {
+            let set = $e::A | $e::C | $e::E;
+            let words: Vec<_> = set.words().collect();
+            assert_eq!(words.len(), (EnumSet::<$e>::REPR_SIZE_BYTES + 7) / 8);
+            assert_eq!(set.words().len(), words.len());
+            let bits = set.as_u128();
+            assert_eq!(words[0], bits as u64);
+            if words.len() > 1 {
+                assert_eq!(words[1], (bits >> 64) as u64);
+            }
+            assert!(EMPTY_SET.words().all(|w| w == 0));
+        }
+
+        #[test]
+        fn iter_indexed_test() {
+            let set = $e::A | $e::C | $e::E;
+            let mut iter = set.iter_indexed();
+            assert_eq!(iter.len(), 3);
+            for (idx, v) in &mut iter {
+                assert_eq!(EnumSet::only(v).as_u128(), 1 << idx);
+            }
+            assert_eq!(iter.len(), 0);
+            assert_eq!(set.iter_indexed().map(|(_, v)| v).collect::<EnumSet<_>>(), set);
+        }
+
+        #[test]
+        fn try_for_each_test() {
+            let set = $e::A | $e::C | $e::E;
+            let mut visited = Vec::new();
+            let result = set.try_for_each(|v| {
+                visited.push(v);
+                if v == $e::C { Err(v) } else { Ok(()) }
+            });
+            assert_eq!(result, Err($e::C));
+            assert_eq!(visited, vec![$e::A, $e::C]);
+            let mut visited = Vec::new();
+            assert_eq!(set.try_for_each(|v| { visited.push(v); Ok::<_, ()>(()) }), Ok(()));
+            assert_eq!(visited, vec![$e::A, $e::C, $e::E]);
+        }
+
+        #[test]
+        fn drain_test() {
+            let mut set = $e::A | $e::C | $e::E;
+            {
+                let mut drain = set.drain();
+                assert_eq!(drain.len(), 3);
+                assert_eq!(drain.next(), Some($e::A));
+                assert_eq!(drain.len(), 2);
+            }
+            assert_eq!(set, $e::C | $e::E);
+            assert_eq!(set.drain().collect::<Vec<_>>(), vec![$e::C, $e::E]);
+            assert!(set.is_empty());
+        }
+
+        #[test]
+        fn iter_complement_test() {
+            let set = $e::A | $e::C | $e::E;
+            let complement: Vec<_> = set.iter_complement().collect();
+            assert_eq!(complement.len(), EnumSet::<$e>::variant_count() as usize - 3);
+            assert_eq!(complement.into_iter().collect::<EnumSet<_>>(), !set);
+            assert_eq!(EnumSet::<$e>::all().iter_complement().count(), 0);
+        }
+
+        #[test]
+        fn single_missing_test() {
+            assert_eq!((EnumSet::<$e>::all() - $e::C).single_missing(), Some($e::C));
+            assert_eq!((EnumSet::<$e>::all() - ($e::A | $e::C)).single_missing(), None);
+            assert_eq!(EnumSet::<$e>::all().single_missing(), None);
+        }
+
+        #[test]
+        fn first_absent_test() {
+            assert_eq!(EMPTY_SET.first_absent(), EnumSet::<$e>::all().iter().next());
+            let all = EnumSet::<$e>::all();
+            assert_eq!((all - $e::C).first_absent(), Some($e::C));
+            assert_eq!(EnumSet::<$e>::all().first_absent(), None);
+            let set = $e::B | $e::C;
+            assert_eq!(set.first_absent(), set.complement().iter().next());
+        }
+
+        #[test]
+        fn complement_within_test() {
+            let universe = $e::A | $e::B | $e::C | $e::D;
+            let set = $e::B | $e::D | $e::E;
+            assert_eq!(set.complement_within(universe), $e::A | $e::C);
+            assert_eq!(set.complement_within(EnumSet::all()), set.complement());
+            assert!(set.complement_within(EnumSet::empty()).is_empty());
+        }
+
+        #[test]
+        fn sum_product_test() {
+            let sets = vec![$e::A | $e::B | $e::C, $e::B | $e::C | $e::D, $e::C | $e::E];
+            let union = $e::A | $e::B | $e::C | $e::D | $e::E;
+            assert_eq!(sets.iter().copied().sum::<EnumSet<_>>(), union);
+            assert_eq!(sets.iter().sum::<EnumSet<_>>(), union);
+            assert_eq!(sets.iter().copied().product::<EnumSet<_>>(), $e::C);
+            assert_eq!(sets.iter().product::<EnumSet<_>>(), $e::C);
+            let empty: Vec<EnumSet<$e>> = Vec::new();
+            assert_eq!(empty.iter().sum::<EnumSet<_>>(), EnumSet::empty());
+            assert_eq!(empty.iter().product::<EnumSet<_>>(), EnumSet::all());
+        }
+
         fn check_iter_size_hint(set: EnumSet<$e>) {
             let count = set.len();
             let mut itr = set.iter();
@@ -228,6 +959,111 @@ macro_rules! test_enum {
             assert_eq!($e::A | !$e::A, EnumSet::<$e>::all());
         }
 
+        #[test]
+        fn not_variant_test() {
+            assert_eq!(!$e::A, EnumSet::<$e>::all() - $e::A);
+            assert_eq!(!$e::A, EnumSet::only($e::A).complement());
+            assert!(!(!$e::A).contains($e::A));
+            assert_eq!((!$e::A).len(), EnumSet::<$e>::variant_count() as usize - 1);
+        }
+
+        #[test]
+        fn difference_report_test() {
+            let set_a = $e::A | $e::B | $e::C;
+            let set_b = $e::B | $e::C | $e::D | $e::E;
+            assert_eq!(set_a.difference_report(set_b), ($e::A.into(), $e::D | $e::E));
+            assert_eq!(set_b.difference_report(set_a), ($e::D | $e::E, $e::A.into()));
+            assert_eq!(set_a.difference_report(set_a), (EnumSet::empty(), EnumSet::empty()));
+        }
+
+        #[test]
+        fn merge_preferring_self_test() {
+            let conflicts = [($e::A, $e::B), ($e::C, $e::D)];
+            let base = $e::B | $e::D | $e::E;
+            let layer = EnumSet::only($e::A);
+            assert_eq!(layer.merge_preferring_self(base, &conflicts), $e::A | $e::D | $e::E);
+            assert_eq!(base.merge_preferring_self(layer, &conflicts), base);
+            let both = $e::A | $e::B;
+            assert_eq!(both.merge_preferring_self(base, &conflicts), both | base);
+            assert_eq!(layer.merge_preferring_self(base, &[]), layer | base);
+            assert_eq!(EMPTY_SET.merge_preferring_self(base, &conflicts), base);
+        }
+
+        #[test]
+        fn present_absent_among_test() {
+            let granted = $e::A | $e::B | $e::E;
+            let required = $e::A | $e::C | $e::E;
+            assert_eq!(granted.present_among(required), $e::A | $e::E);
+            assert_eq!(granted.absent_among(required), $e::C);
+            assert_eq!(granted.present_among(EMPTY_SET), EMPTY_SET);
+            assert_eq!(EMPTY_SET.absent_among(required), required);
+            assert_eq!(granted.absent_among(granted), EMPTY_SET);
+        }
+
+        #[test]
+        fn next_present_test() {
+            let set = $e::A | $e::C | $e::E;
+            assert_eq!(set.next_present($e::A), Some($e::C));
+            assert_eq!(set.next_present($e::B), Some($e::C));
+            assert_eq!(set.next_present($e::E), None);
+            assert_eq!(set.prev_present($e::E), Some($e::C));
+            assert_eq!(set.prev_present($e::D), Some($e::C));
+            assert_eq!(set.prev_present($e::A), None);
+            assert_eq!(set.next_present_wrapping($e::E), Some($e::A));
+            assert_eq!(set.prev_present_wrapping($e::A), Some($e::E));
+            assert_eq!(set.next_present_wrapping($e::C), Some($e::E));
+            assert_eq!(EnumSet::only($e::B).next_present_wrapping($e::B), Some($e::B));
+            assert_eq!(EMPTY_SET.next_present_wrapping($e::A), None);
+            assert_eq!(EMPTY_SET.prev_present_wrapping($e::A), None);
+        }
+
+        #[test]
+        fn highest_diff_test() {
+            let set_a = $e::A | $e::B | $e::E;
+            let set_b = $e::B | $e::C | $e::E;
+            assert_eq!(set_a.highest_diff(set_b), Some($e::C));
+            assert_eq!(set_b.highest_diff(set_a), Some($e::C));
+            assert_eq!(set_a.highest_diff(set_a | $e::D), Some($e::D));
+            assert_eq!(set_a.highest_diff(set_a), None);
+        }
+
+        #[test]
+        fn set_len_test() {
+            let set_a = $e::A | $e::B | $e::C;
+            let set_b = $e::B | $e::C | $e::D | $e::E;
+            assert_eq!(set_a.intersection_len(set_b), 2);
+            assert_eq!(set_a.union_len(set_b), 5);
+            assert_eq!(set_a.intersection_len(EnumSet::empty()), 0);
+            assert_eq!(set_a.union_len(EnumSet::empty()), 3);
+            assert_eq!(set_a.difference_len(set_b), 1);
+            assert_eq!(set_b.difference_len(set_a), 2);
+            assert_eq!(set_a.difference_len(EnumSet::empty()), 3);
+        }
+
+        #[test]
+        fn eq_valid_test() {
+            let set = $e::A | $e::C;
+            assert!(set.eq_valid(set));
+            assert!(!set.eq_valid($e::A.into()));
+            let unknown = !EnumSet::<$e>::all().__enumset_underlying;
+            let stray = EnumSet::<$e> { __enumset_underlying: set.__enumset_underlying | unknown };
+            assert!(set.eq_valid(stray));
+        }
+
+        #[test]
+        fn similarity_test() {
+            let set_a = $e::A | $e::B | $e::C;
+            let set_b = $e::B | $e::C | $e::D | $e::E;
+            let empty = EnumSet::<$e>::empty();
+            assert_eq!(set_a.jaccard_similarity(set_b), 2.0 / 5.0);
+            assert_eq!(set_a.jaccard_similarity(set_a), 1.0);
+            assert_eq!(set_a.jaccard_similarity(empty), 0.0);
+            assert_eq!(empty.jaccard_similarity(empty), 0.0);
+            assert_eq!(set_a.overlap_coefficient(set_b), 2.0 / 3.0);
+            assert_eq!(set_a.overlap_coefficient($e::A | $e::B), 1.0);
+            assert_eq!(set_a.overlap_coefficient(empty), 0.0);
+        }
+
         #[test]
         fn mutable_ops_test() {
             let mut set = $e::A | $e::B;
@@ -242,6 +1078,42 @@ macro_rules! test_enum {
             assert_eq!(set, $e::A | $e::E);
         }
 
+        #[test]
+        fn from_predicate() {
+            let set = $e::A | $e::C | $e::E;
+            assert_eq!(EnumSet::from_predicate(|v| set.contains(v)), set);
+            assert_eq!(EnumSet::<$e>::from_predicate(|_| true), EnumSet::all());
+            assert_eq!(EnumSet::<$e>::from_predicate(|_| false), EnumSet::empty());
+            let mut calls = 0;
+            EnumSet::<$e>::from_predicate(|_| { calls += 1; false });
+            assert_eq!(calls, EnumSet::<$e>::variant_count());
+        }
+
+        #[test]
+        fn from_range() {
+            assert_eq!(EnumSet::from_range($e::B..$e::E), $e::B | $e::C | $e::D);
+            assert_eq!(EnumSet::from_range($e::B..=$e::E), $e::B | $e::C | $e::D | $e::E);
+            let head = EnumSet::from_range(..$e::C);
+            assert!(head.contains($e::A) && head.contains($e::B) && !head.contains($e::C));
+            let tail = EnumSet::from_range($e::E..);
+            assert_eq!(tail, EnumSet::all() - EnumSet::from_range(..$e::E));
+            assert!(tail.contains($e::E) && tail.contains($e::G) && !tail.contains($e::D));
+            assert_eq!(EnumSet::<$e>::from_range(..), EnumSet::all());
+            assert_eq!(EnumSet::from_range($e::E..$e::B), EnumSet::empty());
+            assert_eq!(EnumSet::from_range($e::C..$e::C), EnumSet::empty());
+        }
+
+        #[test]
+        fn split_off_test() {
+            let mut set = $e::A | $e::B | $e::D | $e::E;
+            assert_eq!(set.split_off($e::C), $e::D | $e::E);
+            assert_eq!(set, $e::A | $e::B);
+            assert_eq!(set.split_off($e::B), EnumSet::only($e::B));
+            assert_eq!(set, $e::A);
+            assert_eq!(set.split_off($e::A), EnumSet::only($e::A));
+            assert!(set.is_empty());
+        }
+
         #[test]
         fn basic_set_status() {
             assert!(($e::A | $e::B | $e::C).is_disjoint($e::D | $e::E | $e::F));
@@ -250,9 +1122,25 @@ macro_rules! test_enum {
             assert!(!($e::A | $e::D).is_subset($e::A | $e::B | $e::C));
         }
 
+        #[test]
+        fn cmp_by_elements_test() {
+            use std::cmp::Ordering;
+            let mut sets = vec![
+                $e::B | $e::C, $e::A | $e::D, EnumSet::only($e::A), EnumSet::empty(), $e::A | $e::B,
+            ];
+            sets.sort_by(EnumSet::cmp_by_elements);
+            assert_eq!(sets, vec![
+                EnumSet::empty(), EnumSet::only($e::A), $e::A | $e::B, $e::A | $e::D, $e::B | $e::C,
+            ]);
+            assert_eq!(($e::A | $e::B).cmp_by_elements(&($e::A | $e::B)), Ordering::Equal);
+        }
+
         #[test]
         fn debug_impl() {
             assert_eq!(format!("{:?}", $e::A | $e::B | $e::D), "EnumSet(A | B | D)");
+            let set = $e::A | $e::B | $e::D;
+            assert_eq!(format!("{:#?}", set), format!("EnumSet(A | B | D) [{:#x}]", set.as_u128()));
+            assert_eq!(format!("{:#?}", EnumSet::<$e>::empty()), "EnumSet() [0x0]");
         }
 
         #[test]
@@ -261,6 +1149,80 @@ macro_rules! test_enum {
             assert_eq!(EnumSet::from_u128(value.as_u128()), value);
         }
 
+        #[test]
+        fn from_repr_deny_unknown() {
+            let value = $e::A | $e::C | $e::D;
+            assert_eq!(EnumSet::from_repr_deny_unknown(value.as_repr()), Ok(value));
+            let all = EnumSet::<$e>::all().as_repr();
+            if EnumSet::<$e>::variant_count() as usize != EnumSet::<$e>::REPR_SIZE_BYTES * 8 {
+                let err = EnumSet::<$e>::from_repr_deny_unknown(!all).unwrap_err();
+                assert_eq!(err.unknown_bits(), (!all) as u128);
+                let err = EnumSet::<$e>::from_repr_deny_unknown(!all | value.as_repr());
+                assert_eq!(err.unwrap_err().unknown_bits(), (!all) as u128);
+            }
+        }
+
+        #[test]
+        fn to_from_repr() {
+            let value = $e::A | $e::C | $e::D;
+            let all = EnumSet::<$e>::all().as_repr();
+            assert_eq!(EnumSet::from_repr(value.as_repr()), value);
+            assert_eq!(EnumSet::try_from_repr(value.as_repr()), Some(value));
+            assert_eq!(EnumSet::<$e>::from_repr_truncated(!0), EnumSet::all());
+            assert!(EnumSet::<$e>::repr_is_valid(value.as_repr()));
+            assert!(EnumSet::<$e>::repr_is_valid(all));
+            if EnumSet::<$e>::variant_count() as usize != EnumSet::<$e>::REPR_SIZE_BYTES * 8 {
+                assert_eq!(EnumSet::<$e>::try_from_repr(!all), None);
+                assert!(!EnumSet::<$e>::repr_is_valid(!all));
+            }
+        }
+
+        #[test]
+        fn to_from_bytes() {
+            let value = $e::A | $e::C | $e::D | $e::F | $e::E | $e::G;
+            let le = value.to_le_bytes();
+            let be = value.to_be_bytes();
+            assert_eq!(le.len(), EnumSet::<$e>::REPR_SIZE_BYTES);
+            assert_eq!(le[..], value.as_u128().to_le_bytes()[..le.len()]);
+            assert_eq!(be[..], value.as_u128().to_be_bytes()[16 - be.len()..]);
+            assert_eq!(EnumSet::from_le_bytes(le), value);
+            assert_eq!(EnumSet::from_be_bytes(be), value);
+            assert_eq!(EnumSet::try_from_le_bytes(le), Some(value));
+            assert_eq!(EnumSet::from_be_bytes_truncated(be), value);
+
+            let mut invalid = EnumSet::<$e>::all().to_le_bytes();
+            for byte in invalid.iter_mut() {
+                *byte = 0xFF;
+            }
+            if EnumSet::<$e>::variant_count() as usize != invalid.len() * 8 {
+                assert_eq!(EnumSet::<$e>::try_from_le_bytes(invalid), None);
+            }
+            assert_eq!(EnumSet::<$e>::from_le_bytes_truncated(invalid), EnumSet::all());
+        }
+
+        #[test]
+        fn to_from_byte_array() {
+            let value = $e::A | $e::C | $e::D | $e::F | $e::E | $e::G;
+            let bytes: [u8; EnumSet::<$e>::REPR_SIZE_BYTES] = value.as_byte_array();
+            let native = value.as_u128().to_ne_bytes();
+            if cfg!(target_endian = "big") {
+                assert_eq!(bytes[..], native[16 - bytes.len()..]);
+            } else {
+                assert_eq!(bytes[..], native[..bytes.len()]);
+            }
+            assert_eq!(EnumSet::<$e>::from_byte_array(bytes), value);
+            let empty: [u8; EnumSet::<$e>::REPR_SIZE_BYTES] = EMPTY_SET.as_byte_array();
+            assert_eq!(EnumSet::<$e>::from_byte_array(empty), EMPTY_SET);
+        }
+
+        #[test]
+        fn into_u128() {
+            let value = $e::A | $e::C | $e::D;
+            let bits: u128 = value.into();
+            assert_eq!(bits, value.as_u128());
+            assert_eq!(u128::from(EnumSet::<$e>::all()), EnumSet::<$e>::all().as_u128());
+        }
+
         #[test]
         #[should_panic]
         fn too_many_bits() {
@@ -270,6 +1232,54 @@ macro_rules! test_enum {
             EnumSet::<$e>::from_u128(!0);
         }
 
+        #[test]
+        fn from_indices() {
+            let value = $e::A | $e::C | $e::D;
+            let indices = value.iter().map(|v| EnumSet::only(v).as_u128().trailing_zeros());
+            assert_eq!(EnumSet::try_from_indices(indices), Ok(value));
+            assert_eq!(EnumSet::<$e>::try_from_indices(vec![]), Ok(EnumSet::new()));
+            assert_eq!(EnumSet::<$e>::try_from_indices(vec![200, 300]), Err(200));
+            if EnumSet::<$e>::bit_width() < 128 {
+                let bit = EnumSet::<$e>::bit_width();
+                assert_eq!(EnumSet::<$e>::try_from_indices(vec![bit]), Err(bit));
+            }
+        }
+
+        #[test]
+        fn from_indices_truncated_test() {
+            let value = $e::A | $e::C | $e::D;
+            let mut indices: Vec<_> =
+                value.iter().map(|v| EnumSet::only(v).as_u128().trailing_zeros()).collect();
+            assert_eq!(EnumSet::from_indices_truncated(indices.clone()), value);
+            indices.extend([200, 300, !0, EnumSet::<$e>::bit_width()]);
+            assert_eq!(EnumSet::from_indices_truncated(indices), value);
+            assert_eq!(EnumSet::<$e>::from_indices_truncated(vec![]), EMPTY_SET);
+        }
+
+        #[test]
+        fn from_bools_test() {
+            let pairs = vec![($e::A, true), ($e::B, false), ($e::C, true), ($e::A, false)];
+            assert_eq!(EnumSet::from_bools(pairs), $e::A | $e::C);
+            assert_eq!(EnumSet::from_bools(vec![($e::A, false)]), EMPTY_SET);
+        }
+
+        #[test]
+        fn from_slice_unique_test() {
+            assert_eq!(EnumSet::from_slice_unique(&[$e::A, $e::C]), Ok($e::A | $e::C));
+            assert_eq!(EnumSet::from_slice_unique(&[$e::A, $e::C, $e::D, $e::C, $e::A]),
+                       Err($e::C));
+            assert_eq!(EnumSet::<$e>::from_slice_unique(&[]), Ok(EMPTY_SET));
+        }
+
+        #[test]
+        fn try_from_iter_test() {
+            let results: Vec<Result<$e, u32>> = vec![Ok($e::A), Ok($e::C), Ok($e::A)];
+            assert_eq!(EnumSet::try_from_iter(results), Ok($e::A | $e::C));
+            let results: Vec<Result<$e, u32>> = vec![Ok($e::A), Err(1), Ok($e::C), Err(2)];
+            assert_eq!(EnumSet::try_from_iter(results), Err(1));
+            assert_eq!(EnumSet::<$e>::try_from_iter(Vec::<Result<_, u32>>::new()), Ok(EMPTY_SET));
+        }
+
         #[test]
         fn match_const_test() {
             match CONST_SET {
@@ -323,9 +1333,46 @@ macro_rules! test_enum {
             test_set!(tree_set);
         }
 
+        #[test]
+        fn map_test() {
+            let mut map = EnumSetMap::new();
+            assert!(map.is_empty());
+            assert_eq!(map.insert($e::A, 1), None);
+            assert_eq!(map.insert($e::C, 3), None);
+            assert_eq!(map.insert($e::A, 2), Some(1));
+            assert_eq!(map.len(), 2);
+            assert_eq!(map.keys(), $e::A | $e::C);
+            assert_eq!(map.get($e::A), Some(&2));
+            assert_eq!(map.get($e::B), None);
+            *map.get_mut($e::C).unwrap() += 1;
+            assert_eq!(map.iter().collect::<Vec<_>>(), vec![($e::A, &2), ($e::C, &4)]);
+            assert_eq!(map.clone().remove($e::C), Some(4));
+            assert_eq!(map.remove($e::B), None);
+            assert!(map.contains_key($e::C));
+            map.clear();
+            assert!(map.is_empty());
+            assert_eq!(map.get($e::C), None);
+        }
+
         #[test]
         fn check_size() {
             assert_eq!(::std::mem::size_of::<EnumSet<$e>>(), $mem_size);
+            assert_eq!(EnumSet::<$e>::repr_size_bytes(), $mem_size);
+            assert_eq!(EnumSet::<$e>::REPR_SIZE_BYTES, $mem_size);
+        }
+
+        #[test]
+        fn canonical_hash_bits() {
+            let value = $e::A | $e::C | $e::D;
+            assert_eq!(value.canonical_hash_bits(), value.as_u128());
+            assert_eq!(EnumSet::<$e>::empty().canonical_hash_bits(), 0);
+        }
+
+        #[test]
+        fn as_repr() {
+            let value = $e::A | $e::C | $e::D;
+            assert_eq!(value.as_repr() as u128, value.as_u128());
+            assert_eq!(::std::mem::size_of_val(&value.as_repr()), $mem_size);
         }
     }
 }
@@ -342,6 +1389,165 @@ tests!(repr_enum_u32, test_enum!(ReprEnum, 4));
 tests!(repr_enum_u64, test_enum!(ReprEnum2, 4));
 tests!(repr_enum_isize, test_enum!(ReprEnum3, 4));
 tests!(repr_enum_c, test_enum!(ReprEnum4, 4));
+tests!(repr_override_enum, test_enum!(ReprOverrideEnum, 4));
+tests!(mask_enum, test_enum!(MaskEnum, 1));
+tests!(large_mask_enum, test_enum!(LargeMaskEnum, 16));
+
+#[test]
+fn iter_ranges() {
+    use SparseEnum::*;
+    assert_eq!(EnumSet::<SparseEnum>::all().iter_ranges().count(), 8);
+    assert_eq!(EnumSet::<Enum128>::all().iter_ranges().collect::<Vec<_>>(),
+               vec![(Enum128::A, Enum128::_127)]);
+    let set = EnumSet::<Enum128>::all() - Enum128::_64;
+    assert_eq!(set.iter_ranges().collect::<Vec<_>>(),
+               vec![(Enum128::A, Enum128::_63), (Enum128::_65, Enum128::_127)]);
+    assert_eq!((A | C).iter_ranges().collect::<Vec<_>>(), vec![(A, A), (C, C)]);
+}
+
+#[test]
+fn from_bits_signed() {
+    assert_eq!(enum_set_from_bits!(Enum8, 0b101), Enum8::A | Enum8::C);
+    assert_eq!(enum_set_from_bits_truncated!(Enum8, 0x105i64), Enum8::A | Enum8::C);
+}
+
+#[test]
+#[should_panic]
+fn from_bits_negative() {
+    let bits = -1i8;
+    enum_set_from_bits_truncated!(Enum8, bits);
+}
+
+#[test]
+fn into_narrow() {
+    let set = Enum8::A | Enum8::C;
+    assert_eq!(u8::from(set), 0b101);
+    assert_eq!(u16::from(set), 0b101);
+    assert_eq!(u64::from(set), 0b101);
+    let set = SmallEnum::A | SmallEnum::Z;
+    assert_eq!(u32::from(set), set.as_u32());
+    assert_eq!(u64::from(set), set.as_u64());
+}
+
+#[test]
+fn try_from_wide() {
+    use std::convert::TryInto;
+    let result: Result<EnumSet<Enum8>, _> = (0x1_0000_0003u64).try_into();
+    assert_eq!(result.unwrap_err().unknown_bits(), 0x1_0000_0000);
+    let result: Result<EnumSet<Enum8>, _> = (0x83u128).try_into();
+    assert_eq!(result, Ok(Enum8::A | Enum8::B | Enum8::H));
+}
+
+#[test]
+fn fits_in() {
+    const _: () = assert!(EnumSet::<Enum8>::FITS_IN_U8);
+    const _: () = assert!(!EnumSet::<SmallEnum>::FITS_IN_U16);
+    const _: () = assert!(EnumSet::<SmallEnum>::FITS_IN_U32);
+    const _: () = assert!(!EnumSet::<SparseEnum>::FITS_IN_U64);
+    const _: () = assert!(EnumSet::<EmptyEnum>::FITS_IN_U8);
+    const _: () = assert!(EnumSet::<Enum1>::FITS_IN_U8);
+    const _: () = assert!(EnumSet::<ReprOverrideEnum>::FITS_IN_U8);
+}
+
+#[test]
+fn bit_table() {
+    let table = (SparseEnum::A | SparseEnum::H).bit_table().to_string();
+    let lines: Vec<_> = table.lines().collect();
+    assert_eq!(lines.len(), 8);
+    assert_eq!(lines[0], "10  A  [x]");
+    assert_eq!(lines[1], "20  B  [ ]");
+    assert_eq!(lines[7], "80  H  [x]");
+    assert_eq!(EnumSet::only(Enum8::B).bit_table().to_string().lines().nth(1), Some("1  B  [x]"));
+    assert_eq!(EnumSet::<EmptyEnum>::empty().bit_table().to_string(), "");
+    let table = EnumSet::only(LargeMaskEnum::H).bit_table().to_string();
+    assert_eq!(table.lines().next(), Some("  0  A  [ ]"));
+    assert_eq!(table.lines().last(), Some("127  H  [x]"));
+}
+
+#[test]
+#[should_panic]
+fn from_dense_index_out_of_range() {
+    EnumSet::<SparseEnum>::from_dense_index(1 << 8);
+}
+
+#[test]
+fn bool_array() {
+    let set = Enum8::A | Enum8::C;
+    let array: [bool; 8] = set.to_bool_array();
+    assert_eq!(array, [true, false, true, false, false, false, false, false]);
+    assert_eq!(EnumSet::from_bool_array(array), set);
+    let array: [bool; 81] = (SparseEnum::A | SparseEnum::H).to_bool_array();
+    assert_eq!(array.iter().filter(|&&x| x).count(), 2);
+    assert!(array[10] && array[80]);
+    assert_eq!(EnumSet::<SparseEnum>::from_bool_array([true; 81]), EnumSet::all());
+}
+
+#[test]
+#[should_panic]
+fn from_byte_array_invalid() {
+    EnumSet::<SparseEnum>::from_byte_array([0xFF; 16]);
+}
+
+#[test]
+#[should_panic]
+fn shift_above_invalid() {
+    (SparseEnum::A | SparseEnum::B).shift_above(20, 1);
+}
+
+#[test]
+fn shift_above_collision() {
+    let set = Enum8::A | Enum8::B | Enum8::D;
+    assert_eq!(set.try_shift_above(3, -1), Some(Enum8::A | Enum8::B | Enum8::C));
+    assert_eq!(set.try_shift_above(3, -2), None);
+    assert_eq!(set.shift_above_truncated(3, -2), Enum8::A | Enum8::B);
+}
+
+#[test]
+#[should_panic]
+fn shift_above_collision_panics() {
+    (Enum8::A | Enum8::D).shift_above(3, -3);
+}
+
+#[cfg(feature = "enum-map")]
+#[derive(EnumSetType, enum_map::Enum, Debug)]
+pub enum EnumMapKey {
+    A = 3, B = 1, C = 10, D = 0,
+}
+
+#[cfg(feature = "enum-map")]
+#[test]
+fn enum_map() {
+    use enum_map::{enum_map, EnumMap};
+    let map: EnumMap<EnumMapKey, bool> = enum_map! {
+        EnumMapKey::A | EnumMapKey::C => true,
+        _ => false,
+    };
+    let set = EnumSet::from_enum_map(&map);
+    assert_eq!(set, EnumMapKey::A | EnumMapKey::C);
+    assert_eq!(set.to_enum_map(), map);
+    assert_eq!(EnumSet::<EnumMapKey>::all().to_enum_map(), enum_map! { _ => true });
+    assert!(EnumSet::from_enum_map(&EnumMap::<EnumMapKey, bool>::default()).is_empty());
+}
+
+#[test]
+fn map_storage_size() {
+    use std::mem::{align_of, size_of};
+    // One `Option<u8>` slot per bit up to the highest discriminant, not per bit of the repr.
+    assert_eq!(size_of::<EnumSetMap<ReprOverrideEnum, u8>>(), 4 + 8 * 2);
+    // `SparseEnum` has 8 variants, but its highest discriminant is 80.
+    let align = align_of::<EnumSetMap<SparseEnum, u8>>();
+    let unpadded = 16 + 81 * 2;
+    assert_eq!(size_of::<EnumSetMap<SparseEnum, u8>>(), (unpadded + align - 1) / align * align);
+}
+
+#[test]
+fn subset_count() {
+    const _: () = assert!(EnumSet::<Enum8>::SUBSET_COUNT == 256);
+    assert_eq!(EnumSet::<EmptyEnum>::SUBSET_COUNT, 1);
+    assert_eq!(EnumSet::<Enum1>::SUBSET_COUNT, 2);
+    assert_eq!(EnumSet::<SparseEnum>::SUBSET_COUNT, 1 << 8);
+    assert_eq!(EnumSet::<Enum128>::SUBSET_COUNT, u128::MAX);
+}
 
 #[derive(EnumSetType, Debug)]
 pub enum ThresholdEnum {
@@ -351,7 +1557,7 @@ pub enum ThresholdEnum {
 macro_rules! bits_tests {
     (
         $mod_name:ident, $threshold_expr:expr, ($($too_big_expr:expr),*), $ty:ty,
-        $to:ident $try_to:ident $to_truncated:ident
+        $to:ident $try_to:ident $to_truncated:ident $to_saturating:ident
         $from:ident $try_from:ident $from_truncated:ident
     ) => {
         mod $mod_name {
@@ -367,6 +1573,7 @@ macro_rules! bits_tests {
                 ] {
                     assert_eq!(mask, EnumSet::<ThresholdEnum>::$from(mask.$to()));
                     assert_eq!(mask.$to_truncated(), mask.$to());
+                    assert_eq!(mask.$to_saturating(), mask.$to());
                     assert_eq!(Some(mask.$to()), mask.$try_to())
                 }
             }
@@ -383,11 +1590,22 @@ macro_rules! bits_tests {
                 assert!(EnumSet::<ThresholdEnum>::$try_from(0xFF).is_none());
             }
 
+            #[test]
+            fn try_from_trait() {
+                use std::convert::TryFrom;
+                let mask = $threshold_expr | B | D;
+                assert_eq!(EnumSet::<ThresholdEnum>::try_from(mask.$to()), Ok(mask));
+                let invalid: $ty = 0x80 | 0x04;
+                let err = EnumSet::<ThresholdEnum>::try_from(invalid).unwrap_err();
+                assert_eq!(err.unknown_bits(), 0x80);
+            }
+
             $(
                 #[test]
                 fn try_to_overflow() {
                         let set: EnumSet<ThresholdEnum> = $too_big_expr.into();
                         assert!(set.$try_to().is_none());
+                        assert_eq!(set.$to_saturating(), <$ty>::MAX);
                 }
             )*
 
@@ -404,16 +1622,43 @@ macro_rules! bits_tests {
     }
 }
 
+#[test]
+fn signed_bits() {
+    let set = ThresholdEnum::A | ThresholdEnum::D | ThresholdEnum::U8;
+    assert_eq!(set.as_i8(), 0b10011);
+    assert_eq!(set.as_i32(), set.as_u32() as i32);
+    assert_eq!(EnumSet::<ThresholdEnum>::from_i64(0b10011), set);
+
+    let high = ThresholdEnum::U64 | ThresholdEnum::A;
+    assert_eq!(high.as_i128(), (1 << 32) | 2);
+    assert_eq!(high.try_as_i32(), None);
+    assert_eq!(high.as_i32_truncated(), 2);
+
+    let sign = EnumSet::<ThresholdEnum>::from_u128(1 << 64);
+    assert_eq!(sign.try_as_i64(), None);
+    assert_eq!(EnumSet::<ThresholdEnum>::try_from_i8(-1), None);
+    assert_eq!(EnumSet::<ThresholdEnum>::from_i8_truncated(-1), EnumSet::from_u8(0x1F));
+    assert_eq!(EnumSet::<Enum8>::from_i8(-1), EnumSet::all());
+    assert_eq!(EnumSet::<Enum8>::all().as_i8(), -1);
+    assert_eq!(EnumSet::<Enum8>::all().as_i16(), 0xFF);
+    assert_eq!(EnumSet::<Enum8>::try_from_isize(-1), None);
+}
+
 bits_tests!(test_u8_bits, U8, (U16), u8,
-            as_u8 try_as_u8 as_u8_truncated from_u8 try_from_u8 from_u8_truncated);
+            as_u8 try_as_u8 as_u8_truncated as_u8_saturating
+            from_u8 try_from_u8 from_u8_truncated);
 bits_tests!(test_u16_bits, U16, (U32), u16,
-            as_u16 try_as_u16 as_u16_truncated from_u16 try_from_u16 from_u16_truncated);
+            as_u16 try_as_u16 as_u16_truncated as_u16_saturating
+            from_u16 try_from_u16 from_u16_truncated);
 bits_tests!(test_u32_bits, U32, (U64), u32,
-            as_u32 try_as_u32 as_u32_truncated from_u32 try_from_u32 from_u32_truncated);
+            as_u32 try_as_u32 as_u32_truncated as_u32_saturating
+            from_u32 try_from_u32 from_u32_truncated);
 bits_tests!(test_u64_bits, U64, (U128), u64,
-            as_u64 try_as_u64 as_u64_truncated from_u64 try_from_u64 from_u64_truncated);
+            as_u64 try_as_u64 as_u64_truncated as_u64_saturating
+            from_u64 try_from_u64 from_u64_truncated);
 bits_tests!(test_u128_bits, U128, (), u128,
-            as_u128 try_as_u128 as_u128_truncated from_u128 try_from_u128 from_u128_truncated);
+            as_u128 try_as_u128 as_u128_truncated as_u128_saturating
+            from_u128 try_from_u128 from_u128_truncated);
 bits_tests!(test_uize_bits, U32, (U128), usize,
-            as_usize try_as_usize as_usize_truncated
+            as_usize try_as_usize as_usize_truncated as_usize_saturating
             from_usize try_from_usize from_usize_truncated);
\ No newline at end of file
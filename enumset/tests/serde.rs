@@ -16,6 +16,39 @@ pub enum ListEnum {
     A, B, C, D, E, F, G, H,
 }
 
+#[derive(Serialize, Deserialize, EnumSetType, Debug)]
+#[enumset(serialize_as_list, serialize_in_definition_order)]
+#[serde(crate="serde2")]
+pub enum DefinitionOrderListEnum {
+    C = 2, A = 0, H = 7, B = 1, D = 3, E = 4, F = 5, G = 6,
+}
+
+#[derive(Serialize, Deserialize, EnumSetType, Debug)]
+#[enumset(serialize_as_list, serialize_accept_legacy)]
+#[serde(crate="serde2")]
+pub enum LegacyListEnum {
+    A, B, C, D, E, F, G, H,
+}
+
+#[derive(Serialize, Deserialize, EnumSetType, Debug)]
+#[enumset(serialize_as_list, serialize_accept_legacy, serialize_deny_unknown)]
+#[serde(crate="serde2")]
+pub enum LegacyListDenyUnknownEnum {
+    A, B, C, D, E, F, G, H,
+}
+
+#[derive(EnumSetType, Debug)]
+#[enumset(serialize_as_bits_struct)]
+pub enum BitsStructEnum {
+    A, B, C, D, E, F, G, H,
+}
+
+#[derive(EnumSetType, Debug)]
+#[enumset(serialize_as_bits_struct, serialize_repr = "u32", serialize_deny_unknown)]
+pub enum BitsStructDenyUnknownEnum {
+    A, B, C, D, E, F, G, H,
+}
+
 #[derive(EnumSetType, Debug)]
 #[enumset(serialize_repr = "u128")]
 pub enum ReprEnum {
@@ -28,6 +61,38 @@ pub enum DenyUnknownEnum {
     A, B, C, D, E, F, G, H,
 }
 
+#[derive(EnumSetType, Debug)]
+#[enumset(serialize_repr = "varint")]
+pub enum VarintEnum {
+    A, B, C, D, E, F, G, H, _8, _9, _10, _11, _12, _13, _14, _15,
+    _16, _17, _18, _19, _20, _21, _22, _23, _24, _25, _26, _27, _28, _29, _30, _31,
+    _32, _33, _34, _35, _36, _37, _38, _39, _40, _41, _42, _43, _44, _45, _46, _47,
+    _48, _49, _50, _51, _52, _53, _54, _55, _56, _57, _58, _59, _60, _61, _62, _63,
+    _64, _65, _66, _67, _68, _69, _70, _71, _72, _73, _74, _75, _76, _77, _78, _79,
+    _80, _81, _82, _83, _84, _85, _86, _87, _88, _89, _90, _91, _92, _93, _94, _95,
+    _96, _97, _98, _99, _100, _101, _102, _103, _104, _105, _106, _107, _108, _109,
+    _110, _111, _112, _113, _114, _115, _116, _117, _118, _119, _120, _121, _122,
+    _123, _124,  _125, _126, _127,
+}
+
+#[derive(EnumSetType, Debug)]
+#[enumset(serialize_repr = "varint", serialize_deny_unknown)]
+pub enum VarintDenyUnknownEnum {
+    A, B, C, D, E, F, G, H,
+}
+
+#[derive(EnumSetType, Debug)]
+#[enumset(serialize_human_readable_names)]
+pub enum NamesEnum {
+    A, B, C, D, E, F, G, r#H,
+}
+
+#[derive(EnumSetType, Debug)]
+#[enumset(serialize_human_readable_names, serialize_deny_unknown)]
+pub enum NamesDenyUnknownEnum {
+    A, B, C, D, E, F, G, H,
+}
+
 macro_rules! serde_test_simple {
     ($e:ident, $ser_size:expr) => {
         #[test]
@@ -85,6 +150,139 @@ fn test_json_reprs() {
                serde_json::to_string(&(ReprEnum::A | ReprEnum::C | ReprEnum::D)).unwrap());
 }
 
+#[test]
+fn test_definition_order() {
+    let value = DefinitionOrderListEnum::A | DefinitionOrderListEnum::C |
+        DefinitionOrderListEnum::H | DefinitionOrderListEnum::B;
+    assert_eq!(serde_json::to_string(&value).unwrap(), r#"["C","A","H","B"]"#);
+    assert_eq!(serde_json::from_str::<EnumSet<DefinitionOrderListEnum>>(r#"["A","B","C","H"]"#)
+                   .unwrap(), value);
+}
+
+#[test]
+fn test_accept_legacy() {
+    let value = LegacyListEnum::A | LegacyListEnum::C | LegacyListEnum::D;
+    assert_eq!(serde_json::to_string(&value).unwrap(), r#"["A","C","D"]"#);
+    assert_eq!(serde_json::from_str::<EnumSet<LegacyListEnum>>(r#"["A","C","D"]"#).unwrap(),
+               value);
+    assert_eq!(serde_json::from_str::<EnumSet<LegacyListEnum>>("13").unwrap(), value);
+    assert_eq!(serde_json::from_str::<EnumSet<LegacyListEnum>>("269").unwrap(), value);
+    assert!(serde_json::from_str::<EnumSet<LegacyListEnum>>("-1").is_err());
+
+    assert_eq!(serde_json::from_str::<EnumSet<LegacyListDenyUnknownEnum>>("13").unwrap(),
+               LegacyListDenyUnknownEnum::A | LegacyListDenyUnknownEnum::C |
+               LegacyListDenyUnknownEnum::D);
+    assert!(serde_json::from_str::<EnumSet<LegacyListDenyUnknownEnum>>("269").is_err());
+}
+
+#[test]
+fn test_bits_struct() {
+    let value = BitsStructEnum::A | BitsStructEnum::C | BitsStructEnum::D;
+    assert_eq!(serde_json::to_string(&value).unwrap(), r#"{"bits":13}"#);
+    assert_eq!(serde_json::from_str::<EnumSet<BitsStructEnum>>(r#"{"bits":13}"#).unwrap(), value);
+    assert_eq!(serde_json::from_str::<EnumSet<BitsStructEnum>>(r#"{"x":[1],"bits":13}"#).unwrap(),
+               value);
+    assert_eq!(serde_json::from_str::<EnumSet<BitsStructEnum>>("[13]").unwrap(), value);
+    assert!(serde_json::from_str::<EnumSet<BitsStructEnum>>("{}").is_err());
+    assert!(serde_json::from_str::<EnumSet<BitsStructEnum>>(r#"{"bits":1,"bits":1}"#).is_err());
+    assert!(serde_json::from_str::<EnumSet<BitsStructEnum>>("13").is_err());
+
+    let deny = BitsStructDenyUnknownEnum::A | BitsStructDenyUnknownEnum::H;
+    assert_eq!(bincode::serialize(&deny).unwrap(), vec![0x81, 0, 0, 0]);
+    assert!(serde_json::from_str::<EnumSet<BitsStructDenyUnknownEnum>>(r#"{"bits":256}"#)
+        .is_err());
+}
+
+#[test]
+fn test_varint() {
+    let small = VarintEnum::A | VarintEnum::G;
+    assert_eq!(bincode::serialize(&small).unwrap(), vec![0x41]);
+    let large = VarintEnum::A | VarintEnum::H | VarintEnum::_127;
+    let serialized = bincode::serialize(&large).unwrap();
+    assert_eq!(serialized.len(), 19);
+    assert_eq!(bincode::deserialize::<EnumSet<VarintEnum>>(&serialized).unwrap(), large);
+    let all = EnumSet::<VarintEnum>::all();
+    let serialized = bincode::serialize(&all).unwrap();
+    assert_eq!(bincode::deserialize::<EnumSet<VarintEnum>>(&serialized).unwrap(), all);
+    assert_eq!(serde_json::to_string(&(VarintEnum::A | VarintEnum::H)).unwrap(), "[129,1]");
+    assert_eq!(serde_json::from_str::<EnumSet<VarintEnum>>("[129,1]").unwrap(),
+               VarintEnum::A | VarintEnum::H);
+    assert!(serde_json::from_str::<EnumSet<VarintEnum>>("[129]").is_err());
+    assert!(serde_json::from_str::<EnumSet<VarintEnum>>("[1,1]").is_err());
+}
+
+fn serialize_as_width<T: EnumSetType>(
+    set: EnumSet<T>, width: ReprWidth,
+) -> bincode::Result<Vec<u8>> {
+    use bincode::Options;
+    let options = bincode::DefaultOptions::new().with_fixint_encoding();
+    let mut serialized = Vec::new();
+    set.serialize_as_width(width, &mut bincode::Serializer::new(&mut serialized, options))?;
+    Ok(serialized)
+}
+fn deserialize_as_width<T: EnumSetType>(
+    serialized: &[u8], width: ReprWidth,
+) -> bincode::Result<EnumSet<T>> {
+    use bincode::Options;
+    let options = bincode::DefaultOptions::new().with_fixint_encoding();
+    let mut deserializer = bincode::Deserializer::from_slice(serialized, options);
+    EnumSet::deserialize_as_width(width, &mut deserializer)
+}
+
+#[test]
+fn test_runtime_width() {
+    let value = VarintEnum::A | VarintEnum::C | VarintEnum::H;
+    let bits = value.as_u128();
+    let expected = [
+        (ReprWidth::U8, bincode::serialize(&(bits as u8)).unwrap()),
+        (ReprWidth::U16, bincode::serialize(&(bits as u16)).unwrap()),
+        (ReprWidth::U32, bincode::serialize(&(bits as u32)).unwrap()),
+        (ReprWidth::U64, bincode::serialize(&(bits as u64)).unwrap()),
+        (ReprWidth::U128, bincode::serialize(&bits).unwrap()),
+        (ReprWidth::Varint, bincode::serialize(&value).unwrap()),
+    ];
+    for (width, bytes) in expected.iter() {
+        assert_eq!(&serialize_as_width(value, *width).unwrap(), bytes);
+        assert_eq!(deserialize_as_width::<VarintEnum>(bytes, *width).unwrap(), value);
+    }
+
+    let large = VarintEnum::A | VarintEnum::_8;
+    assert!(serialize_as_width(large, ReprWidth::U8).is_err());
+    assert_eq!(serialize_as_width(large, ReprWidth::U16).unwrap(), vec![1, 1]);
+
+    let unknown = bincode::serialize(&0xFFFFu16).unwrap();
+    assert_eq!(deserialize_as_width::<VarintDenyUnknownEnum>(&unknown, ReprWidth::U16).unwrap(),
+               EnumSet::all());
+}
+
+#[test]
+fn test_varint_deny_unknown() {
+    assert_eq!(serde_json::from_str::<EnumSet<VarintDenyUnknownEnum>>("[127]").unwrap(),
+               EnumSet::from_u8(127));
+    assert!(serde_json::from_str::<EnumSet<VarintDenyUnknownEnum>>("[128,2]").is_err());
+}
+
+#[test]
+fn test_human_readable_names() {
+    let value = NamesEnum::A | NamesEnum::C | NamesEnum::H;
+    assert_eq!(serde_json::to_string(&value).unwrap(), r#"["A","C","H"]"#);
+    assert_eq!(serde_json::from_str::<EnumSet<NamesEnum>>(r#"["H","A","C"]"#).unwrap(), value);
+    assert_eq!(serde_json::from_str::<EnumSet<NamesEnum>>(r#"["A","X"]"#).unwrap(),
+               NamesEnum::A);
+    assert_eq!(bincode::serialize(&value).unwrap(), vec![0x85]);
+    assert!(serde_json::from_str::<EnumSet<NamesEnum>>("[1]").is_err());
+
+    let deserialized = serde_json::from_str::<EnumSet<NamesDenyUnknownEnum>>(r#"["A","X"]"#);
+    assert!(deserialized.is_err());
+}
+
 tests!(list_enum, serde_test_simple!(ListEnum, !0));
+tests!(definition_order_list_enum, serde_test_simple!(DefinitionOrderListEnum, !0));
 tests!(repr_enum, serde_test!(ReprEnum, 16));
 tests!(deny_unknown_enum, serde_test_simple!(DenyUnknownEnum, 16));
+tests!(varint_enum, serde_test_simple!(VarintEnum, !0));
+tests!(varint_deny_unknown_enum, serde_test_simple!(VarintDenyUnknownEnum, 1));
+tests!(names_enum, serde_test_simple!(NamesEnum, 1));
+tests!(names_deny_unknown_enum, serde_test_simple!(NamesDenyUnknownEnum, 1));
+tests!(bits_struct_enum, serde_test_simple!(BitsStructEnum, 1));
+tests!(bits_struct_deny_unknown_enum, serde_test_simple!(BitsStructDenyUnknownEnum, 4));
@@ -7,6 +7,7 @@ use proc_macro::TokenStream;
 use proc_macro2::{TokenStream as SynTokenStream, Literal, Span};
 use std::collections::HashSet;
 use syn::{*, Result, Error};
+use syn::ext::IdentExt;
 use syn::spanned::Spanned;
 use quote::*;
 
@@ -20,11 +21,20 @@ fn error<T>(span: Span, message: &str) -> Result<T> {
 #[darling(attributes(enumset), default)]
 struct EnumsetAttrs {
     no_ops: bool,
+    with_name: bool,
+    with_mask: bool,
+    discriminant_is_mask: bool,
     serialize_as_list: bool,
+    serialize_human_readable_names: bool,
+    serialize_accept_legacy: bool,
+    serialize_in_definition_order: bool,
+    serialize_as_bits_struct: bool,
     serialize_deny_unknown: bool,
     #[darling(default)]
     serialize_repr: Option<String>,
     #[darling(default)]
+    repr: Option<String>,
+    #[darling(default)]
     crate_name: Option<String>,
 }
 
@@ -45,6 +55,8 @@ struct EnumSetInfo {
     crate_name: Option<Ident>,
     /// The numeric type to serialize the enum as.
     explicit_serde_repr: Option<Ident>,
+    /// The numeric type to store the bitset in.
+    explicit_mem_repr: Option<Ident>,
     /// Whether the underlying repr of the enum supports negative values.
     has_signed_repr: bool,
     /// Whether the underlying repr of the enum supports values higher than 2^32.
@@ -56,6 +68,8 @@ struct EnumSetInfo {
     max_discrim: u32,
     /// The current variant discriminant. Used to track, e.g. `A=10,B,C`.
     cur_discrim: u32,
+    /// The current variant discriminant, when discriminants are interpreted as masks.
+    cur_mask: u128,
     /// A list of variant names that are already in use.
     used_variant_names: HashSet<String>,
     /// A list of variant discriminants that are already in use.
@@ -63,8 +77,22 @@ struct EnumSetInfo {
 
     /// Avoid generating operator overloads on the enum type.
     no_ops: bool,
+    /// Generate a `name` method returning the name of each variant.
+    with_name: bool,
+    /// Generate a `mask` method returning the bit mask of each variant.
+    with_mask: bool,
+    /// Interpret variant discriminants as bit masks rather than bit positions.
+    discriminant_is_mask: bool,
     /// Serialize the enum as a list.
     serialize_as_list: bool,
+    /// Serialize the enum as a list of variant names in human readable formats.
+    serialize_human_readable_names: bool,
+    /// Accept the integer format when deserializing an enum serialized as a list.
+    serialize_accept_legacy: bool,
+    /// Serialize the variants of an enum serialized as a list in the order they are declared.
+    serialize_in_definition_order: bool,
+    /// Serialize the enum as a struct with a `bits` field.
+    serialize_as_bits_struct: bool,
     /// Disallow unknown bits while deserializing the enum.
     serialize_deny_unknown: bool,
 }
@@ -74,15 +102,24 @@ impl EnumSetInfo {
             name: input.ident.clone(),
             crate_name: attrs.crate_name.map(|x| Ident::new(&x, Span::call_site())),
             explicit_serde_repr: attrs.serialize_repr.map(|x| Ident::new(&x, Span::call_site())),
+            explicit_mem_repr: attrs.repr.map(|x| Ident::new(&x, Span::call_site())),
             has_signed_repr: false,
             has_large_repr: false,
             variants: Vec::new(),
             max_discrim: 0,
             cur_discrim: 0,
+            cur_mask: 0,
             used_variant_names: HashSet::new(),
             used_discriminants: HashSet::new(),
             no_ops: attrs.no_ops,
+            with_name: attrs.with_name,
+            with_mask: attrs.with_mask,
+            discriminant_is_mask: attrs.discriminant_is_mask,
             serialize_as_list: attrs.serialize_as_list,
+            serialize_human_readable_names: attrs.serialize_human_readable_names,
+            serialize_accept_legacy: attrs.serialize_accept_legacy,
+            serialize_in_definition_order: attrs.serialize_in_definition_order,
+            serialize_as_bits_struct: attrs.serialize_as_bits_struct,
             serialize_deny_unknown: attrs.serialize_deny_unknown
         }
     }
@@ -109,13 +146,59 @@ impl EnumSetInfo {
             _ => error(attr_span, "Unsupported repr.")
         }
     }
+    /// Evaluates a discriminant expression made of integer literals and left shifts.
+    ///
+    /// This is only used with `#[enumset(discriminant_is_mask)]`. By default, discriminants must
+    /// be plain integer literals.
+    fn eval_discriminant(expr: &Expr) -> Option<u128> {
+        match expr {
+            Expr::Lit(ExprLit { lit: Lit::Int(i), .. }) => i.base10_parse().ok(),
+            Expr::Paren(ExprParen { expr, .. }) => Self::eval_discriminant(expr),
+            Expr::Group(ExprGroup { expr, .. }) => Self::eval_discriminant(expr),
+            Expr::Binary(ExprBinary { left, op: BinOp::Shl(_), right, .. }) => {
+                let left = Self::eval_discriminant(left)?;
+                let right = Self::eval_discriminant(right)?;
+                if right < 128 && (left << right) >> right == left {
+                    Some(left << right)
+                } else {
+                    None
+                }
+            }
+            _ => None,
+        }
+    }
+    /// Computes the bit position of a variant whose discriminant is interpreted as a mask.
+    fn push_mask_discriminant(&mut self, variant: &Variant) -> Result<()> {
+        if let Some((_, expr)) = &variant.discriminant {
+            match Self::eval_discriminant(expr) {
+                Some(mask) => self.cur_mask = mask,
+                None => error(
+                    expr.span(),
+                    "Enum set discriminants must be integer literals or shifts of integer \
+                     literals.",
+                )?,
+            }
+        }
+        if !self.cur_mask.is_power_of_two() {
+            error(
+                variant.span(),
+                "`#[enumset(discriminant_is_mask)]` requires every discriminant to be a power \
+                 of two.",
+            )?;
+        }
+        self.cur_discrim = self.cur_mask.trailing_zeros();
+        self.cur_mask = self.cur_mask.wrapping_add(1);
+        Ok(())
+    }
     /// Adds a variant to the enumset.
     fn push_variant(&mut self, variant: &Variant) -> Result<()> {
         if self.used_variant_names.contains(&variant.ident.to_string()) {
             error(variant.span(), "Duplicated variant name.")
         } else if let Fields::Unit = variant.fields {
             // Parse the discriminant.
-            if let Some((_, expr)) = &variant.discriminant {
+            if self.discriminant_is_mask {
+                self.push_mask_discriminant(variant)?;
+            } else if let Some((_, expr)) = &variant.discriminant {
                 let discriminant_fail_message = format!(
                     "Enum set discriminants must be `u32`s.{}",
                     if self.has_signed_repr || self.has_large_repr {
@@ -176,6 +259,27 @@ impl EnumSetInfo {
     }
     /// Validate the enumset type.
     fn validate(&self) -> Result<()> {
+        if self.serialize_as_list && self.serialize_human_readable_names {
+            error(
+                Span::call_site(),
+                "`serialize_as_list` and `serialize_human_readable_names` cannot be used together."
+            )?;
+        }
+        if self.serialize_as_bits_struct && self.serialize_as_list {
+            error(
+                Span::call_site(),
+                "`serialize_as_list` and `serialize_as_bits_struct` cannot be used together."
+            )?;
+        }
+        if self.serialize_accept_legacy && !self.serialize_as_list {
+            error(Span::call_site(), "`serialize_accept_legacy` requires `serialize_as_list`.")?;
+        }
+        if self.serialize_in_definition_order && !self.serialize_as_list {
+            error(
+                Span::call_site(),
+                "`serialize_in_definition_order` requires `serialize_as_list`."
+            )?;
+        }
         // Check if all bits of the bitset can fit in the serialization representation.
         if let Some(explicit_serde_repr) = &self.explicit_serde_repr {
             let is_overflowed = match explicit_serde_repr.to_string().as_str() {
@@ -184,21 +288,46 @@ impl EnumSetInfo {
                 "u32" => self.max_discrim >= 32,
                 "u64" => self.max_discrim >= 64,
                 "u128" => self.max_discrim >= 128,
+                "varint" if self.serialize_as_bits_struct => error(
+                    Span::call_site(),
+                    "`serialize_repr = \"varint\"` cannot be used with `serialize_as_bits_struct`."
+                )?,
+                "varint" => false,
                 _ => error(
                     Span::call_site(),
-                    "Only `u8`, `u16`, `u32`, `u64` and `u128` are supported for serde_repr."
+                    "Only `u8`, `u16`, `u32`, `u64`, `u128` and `varint` are supported for \
+                     serde_repr."
                 )?,
             };
             if is_overflowed {
                 error(Span::call_site(), "serialize_repr cannot be smaller than bitset.")?;
             }
         }
+        // Check if all bits of the bitset can fit in the memory representation.
+        if let Some(explicit_mem_repr) = &self.explicit_mem_repr {
+            let is_overflowed = match explicit_mem_repr.to_string().as_str() {
+                "u8" => self.max_discrim >= 8,
+                "u16" => self.max_discrim >= 16,
+                "u32" => self.max_discrim >= 32,
+                "u64" => self.max_discrim >= 64,
+                "u128" => self.max_discrim >= 128,
+                _ => error(
+                    Span::call_site(),
+                    "Only `u8`, `u16`, `u32`, `u64` and `u128` are supported for repr."
+                )?,
+            };
+            if is_overflowed {
+                error(Span::call_site(), "repr cannot be smaller than bitset.")?;
+            }
+        }
         Ok(())
     }
 
     /// Computes the underlying type used to store the enumset.
     fn enumset_repr(&self) -> SynTokenStream {
-        if self.max_discrim <= 7 {
+        if let Some(mem_repr) = &self.explicit_mem_repr {
+            quote! { #mem_repr }
+        } else if self.max_discrim <= 7 {
             quote! { u8 }
         } else if self.max_discrim <= 15 {
             quote! { u16 }
@@ -212,6 +341,20 @@ impl EnumSetInfo {
             panic!("max_variant > 127?")
         }
     }
+    /// Computes the `ReprWidth` variant used to serialize the enumset.
+    #[cfg(feature = "serde")]
+    fn serde_repr_width(&self) -> Ident {
+        let width = match self.serde_repr().to_string().as_str() {
+            "varint" => "Varint",
+            "u8" => "U8",
+            "u16" => "U16",
+            "u32" => "U32",
+            "u64" => "U64",
+            "u128" => "U128",
+            _ => panic!("unexpected serde_repr"),
+        };
+        Ident::new(width, Span::call_site())
+    }
     /// Computes the underlying type used to serialize the enumset.
     #[cfg(feature = "serde")]
     fn serde_repr(&self) -> SynTokenStream {
@@ -222,6 +365,15 @@ impl EnumSetInfo {
         }
     }
 
+    /// Returns the number of bits used by the variants of the enum.
+    fn bit_width(&self) -> u32 {
+        if self.variants.is_empty() {
+            0
+        } else {
+            self.max_discrim + 1
+        }
+    }
+
     /// Returns a bitmask of all variants in the set.
     fn all_variants(&self) -> u128 {
         let mut accum = 0u128;
@@ -245,30 +397,34 @@ fn enum_set_type_impl(info: EnumSetInfo) -> SynTokenStream {
 
     let repr = info.enumset_repr();
     let all_variants = Literal::u128_unsuffixed(info.all_variants());
+    let bit_width = info.bit_width();
+    let map_len = Literal::usize_unsuffixed(bit_width as usize);
+    let variant_count = info.variants.len() as u32;
+    let discriminant_is_mask = info.discriminant_is_mask;
 
     let ops = if info.no_ops {
         quote! {}
     } else {
         quote! {
-            impl <O : Into<#typed_enumset>> #core::ops::Sub<O> for #name {
+            impl <O : #core::convert::Into<#typed_enumset>> #core::ops::Sub<O> for #name {
                 type Output = #typed_enumset;
                 fn sub(self, other: O) -> Self::Output {
                     #enumset::EnumSet::only(self) - other.into()
                 }
             }
-            impl <O : Into<#typed_enumset>> #core::ops::BitAnd<O> for #name {
+            impl <O : #core::convert::Into<#typed_enumset>> #core::ops::BitAnd<O> for #name {
                 type Output = #typed_enumset;
                 fn bitand(self, other: O) -> Self::Output {
                     #enumset::EnumSet::only(self) & other.into()
                 }
             }
-            impl <O : Into<#typed_enumset>> #core::ops::BitOr<O> for #name {
+            impl <O : #core::convert::Into<#typed_enumset>> #core::ops::BitOr<O> for #name {
                 type Output = #typed_enumset;
                 fn bitor(self, other: O) -> Self::Output {
                     #enumset::EnumSet::only(self) | other.into()
                 }
             }
-            impl <O : Into<#typed_enumset>> #core::ops::BitXor<O> for #name {
+            impl <O : #core::convert::Into<#typed_enumset>> #core::ops::BitXor<O> for #name {
                 type Output = #typed_enumset;
                 fn bitxor(self, other: O) -> Self::Output {
                     #enumset::EnumSet::only(self) ^ other.into()
@@ -288,22 +444,119 @@ fn enum_set_type_impl(info: EnumSetInfo) -> SynTokenStream {
         }
     };
 
+    let name_impl = if info.with_name {
+        let variant_name: Vec<_> = info.variants.iter().map(|x| &x.name).collect();
+        let variant_str: Vec<_> = info.variants.iter()
+            .map(|x| LitStr::new(&x.name.unraw().to_string(), x.name.span())).collect();
+        quote! {
+            impl #name {
+                /// Returns the name of this variant.
+                pub const fn name(self) -> &'static str {
+                    match self {
+                        #(#name::#variant_name => #variant_str,)*
+                    }
+                }
+            }
+            impl #enumset::__internal::EnumSetTypeWithNamePrivate for #name {
+                fn enum_variant_name(self) -> &'static str {
+                    self.name()
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
+    let mask_impl = if info.with_mask {
+        let variant_name: Vec<_> = info.variants.iter().map(|x| &x.name).collect();
+        let variant_mask: Vec<_> = info.variants.iter()
+            .map(|x| Literal::u128_unsuffixed(1u128 << x.variant_repr)).collect();
+        quote! {
+            impl #name {
+                /// Returns the mask of the bit this variant is stored in.
+                pub const fn mask(self) -> u128 {
+                    match self {
+                        #(#name::#variant_name => #variant_mask,)*
+                    }
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
 
     #[cfg(feature = "serde")]
     let serde = quote!(#enumset::__internal::serde);
 
+    #[cfg(feature = "serde")]
+    let check_unknown = if info.serialize_deny_unknown {
+        quote! {
+            if value & !#all_variants != 0 {
+                use #serde::de::Error;
+                return #core::prelude::v1::Err(
+                    D::Error::custom("enumset contains unknown bits")
+                )
+            }
+        }
+    } else {
+        quote! { }
+    };
+
     #[cfg(feature = "serde")]
     let serde_ops = if info.serialize_as_list {
-        let expecting_str = format!("a list of {}", name);
+        let (expecting_str, visit_legacy, deserialize_call) = if info.serialize_accept_legacy {
+            let check_unknown = if info.serialize_deny_unknown {
+                quote! {
+                    if value & !#all_variants != 0 {
+                        return #core::prelude::v1::Err(E::custom("enumset contains unknown bits"))
+                    }
+                }
+            } else {
+                quote! { }
+            };
+            (
+                format!("a list of {} or an integer", name),
+                quote! {
+                    fn visit_u64<E: #serde::de::Error>(
+                        self, value: u64,
+                    ) -> #core::result::Result<Self::Value, E> {
+                        self.visit_u128(value as u128)
+                    }
+                    fn visit_u128<E: #serde::de::Error>(
+                        self, value: u128,
+                    ) -> #core::result::Result<Self::Value, E> {
+                        #check_unknown
+                        #core::prelude::v1::Ok(#enumset::EnumSet {
+                            __enumset_underlying: (value & #all_variants) as #repr,
+                        })
+                    }
+                },
+                quote! { de.deserialize_any(Visitor) },
+            )
+        } else {
+            (format!("a list of {}", name), quote! { }, quote! { de.deserialize_seq(Visitor) })
+        };
+        let serialize_elements = if info.serialize_in_definition_order {
+            let variant_name: Vec<_> = info.variants.iter().map(|x| &x.name).collect();
+            quote! {
+                #(if set.contains(#name::#variant_name) {
+                    seq.serialize_element(&#name::#variant_name)?;
+                })*
+            }
+        } else {
+            quote! {
+                for bit in set {
+                    seq.serialize_element(&bit)?;
+                }
+            }
+        };
         quote! {
             fn serialize<S: #serde::Serializer>(
                 set: #enumset::EnumSet<#name>, ser: S,
             ) -> #core::result::Result<S::Ok, S::Error> {
                 use #serde::ser::SerializeSeq;
                 let mut seq = ser.serialize_seq(#core::prelude::v1::Some(set.len()))?;
-                for bit in set {
-                    seq.serialize_element(&bit)?;
-                }
+                #serialize_elements
                 seq.end()
             }
             fn deserialize<'de, D: #serde::Deserializer<'de>>(
@@ -315,7 +568,7 @@ fn enum_set_type_impl(info: EnumSetInfo) -> SynTokenStream {
                     fn expecting(
                         &self, formatter: &mut #core::fmt::Formatter,
                     ) -> #core::fmt::Result {
-                        write!(formatter, #expecting_str)
+                        #core::write!(formatter, #expecting_str)
                     }
                     fn visit_seq<A>(
                         mut self, mut seq: A,
@@ -328,38 +581,244 @@ fn enum_set_type_impl(info: EnumSetInfo) -> SynTokenStream {
                         }
                         #core::prelude::v1::Ok(accum)
                     }
+                    #visit_legacy
                 }
-                de.deserialize_seq(Visitor)
+                #deserialize_call
             }
         }
     } else {
-        let serialize_repr = info.serde_repr();
-        let check_unknown = if info.serialize_deny_unknown {
-            quote! {
-                if value & !#all_variants != 0 {
-                    use #serde::de::Error;
-                    return #core::prelude::v1::Err(
-                        D::Error::custom("enumset contains unknown bits")
+        let (serialize_int, deserialize_int) = if info.serialize_as_bits_struct {
+            let serialize_repr = info.serde_repr();
+            let struct_name = name.to_string();
+            let expecting_str = format!("struct {}", name);
+            (
+                quote! {
+                    use #serde::ser::SerializeStruct;
+                    let mut st = ser.serialize_struct(#struct_name, 1)?;
+                    st.serialize_field("bits", &(set.__enumset_underlying as #serialize_repr))?;
+                    st.end()
+                },
+                quote! {
+                    enum Field { Bits, Ignore }
+                    impl <'de> #serde::Deserialize<'de> for Field {
+                        fn deserialize<D: #serde::Deserializer<'de>>(
+                            de: D,
+                        ) -> #core::result::Result<Self, D::Error> {
+                            struct Visitor;
+                            impl <'de> #serde::de::Visitor<'de> for Visitor {
+                                type Value = Field;
+                                fn expecting(
+                                    &self, formatter: &mut #core::fmt::Formatter,
+                                ) -> #core::fmt::Result {
+                                    #core::write!(formatter, "a field identifier")
+                                }
+                                fn visit_u64<E: #serde::de::Error>(
+                                    self, value: u64,
+                                ) -> #core::result::Result<Field, E> {
+                                    #core::prelude::v1::Ok(
+                                        if value == 0 { Field::Bits } else { Field::Ignore }
+                                    )
+                                }
+                                fn visit_str<E: #serde::de::Error>(
+                                    self, value: &str,
+                                ) -> #core::result::Result<Field, E> {
+                                    #core::prelude::v1::Ok(
+                                        if value == "bits" { Field::Bits } else { Field::Ignore }
+                                    )
+                                }
+                                fn visit_bytes<E: #serde::de::Error>(
+                                    self, value: &[u8],
+                                ) -> #core::result::Result<Field, E> {
+                                    #core::prelude::v1::Ok(
+                                        if value == b"bits" { Field::Bits } else { Field::Ignore }
+                                    )
+                                }
+                            }
+                            de.deserialize_identifier(Visitor)
+                        }
+                    }
+
+                    struct Visitor;
+                    impl <'de> #serde::de::Visitor<'de> for Visitor {
+                        type Value = #serialize_repr;
+                        fn expecting(
+                            &self, formatter: &mut #core::fmt::Formatter,
+                        ) -> #core::fmt::Result {
+                            #core::write!(formatter, #expecting_str)
+                        }
+                        fn visit_seq<A>(
+                            self, mut seq: A,
+                        ) -> #core::result::Result<Self::Value, A::Error> where
+                            A: #serde::de::SeqAccess<'de>
+                        {
+                            match seq.next_element()? {
+                                #core::prelude::v1::Some(bits) => #core::prelude::v1::Ok(bits),
+                                #core::prelude::v1::None => #core::prelude::v1::Err(
+                                    #serde::de::Error::invalid_length(0, &self)
+                                ),
+                            }
+                        }
+                        fn visit_map<A>(
+                            self, mut map: A,
+                        ) -> #core::result::Result<Self::Value, A::Error> where
+                            A: #serde::de::MapAccess<'de>
+                        {
+                            let mut bits = #core::prelude::v1::None;
+                            while let #core::prelude::v1::Some(key) = map.next_key()? {
+                                match key {
+                                    Field::Bits => {
+                                        if bits.is_some() {
+                                            return #core::prelude::v1::Err(
+                                                #serde::de::Error::duplicate_field("bits")
+                                            );
+                                        }
+                                        bits = #core::prelude::v1::Some(map.next_value()?);
+                                    }
+                                    Field::Ignore => {
+                                        map.next_value::<#serde::de::IgnoredAny>()?;
+                                    }
+                                }
+                            }
+                            bits.ok_or_else(|| #serde::de::Error::missing_field("bits"))
+                        }
+                    }
+
+                    let value = de.deserialize_struct(#struct_name, &["bits"], Visitor)?;
+                    #check_unknown
+                    #core::prelude::v1::Ok(#enumset::EnumSet {
+                        __enumset_underlying: (value & #all_variants) as #repr,
+                    })
+                },
+            )
+        } else {
+            let width = info.serde_repr_width();
+            (
+                quote! {
+                    #enumset::__internal::serialize_with_width(
+                        set.__enumset_underlying as u128, #enumset::ReprWidth::#width, ser,
                     )
+                },
+                quote! {
+                    let value = #enumset::__internal::deserialize_with_width(
+                        #enumset::ReprWidth::#width, de,
+                    )?;
+                    #check_unknown
+                    #core::prelude::v1::Ok(#enumset::EnumSet {
+                        __enumset_underlying: (value & #all_variants) as #repr,
+                    })
+                },
+            )
+        };
+
+        if info.serialize_human_readable_names {
+            let expecting_str = format!("a list of {} variant names", name);
+            let variant_name: Vec<_> = info.variants.iter().map(|x| &x.name).collect();
+            let variant_str: Vec<_> = info.variants.iter()
+                .map(|x| LitStr::new(&x.name.unraw().to_string(), x.name.span())).collect();
+            let unknown_name = if info.serialize_deny_unknown {
+                quote! {
+                    #core::prelude::v1::Err(E::unknown_variant(value, VARIANT_NAMES))
+                }
+            } else {
+                quote! { #core::prelude::v1::Ok(VariantName(#core::prelude::v1::None)) }
+            };
+            quote! {
+                fn serialize<S: #serde::Serializer>(
+                    set: #enumset::EnumSet<#name>, ser: S,
+                ) -> #core::result::Result<S::Ok, S::Error> {
+                    if ser.is_human_readable() {
+                        use #serde::ser::SerializeSeq;
+                        let mut seq = ser.serialize_seq(#core::prelude::v1::Some(set.len()))?;
+                        for bit in set {
+                            let name = match bit {
+                                #(#name::#variant_name => #variant_str,)*
+                            };
+                            seq.serialize_element(name)?;
+                        }
+                        seq.end()
+                    } else {
+                        #serialize_int
+                    }
+                }
+                fn deserialize<'de, D: #serde::Deserializer<'de>>(
+                    de: D,
+                ) -> #core::result::Result<#enumset::EnumSet<#name>, D::Error> {
+                    const VARIANT_NAMES: &[&str] = &[#(#variant_str,)*];
+
+                    struct VariantName(#core::option::Option<#name>);
+                    impl <'de> #serde::Deserialize<'de> for VariantName {
+                        fn deserialize<D: #serde::Deserializer<'de>>(
+                            de: D,
+                        ) -> #core::result::Result<Self, D::Error> {
+                            struct Visitor;
+                            impl <'de> #serde::de::Visitor<'de> for Visitor {
+                                type Value = VariantName;
+                                fn expecting(
+                                    &self, formatter: &mut #core::fmt::Formatter,
+                                ) -> #core::fmt::Result {
+                                    #core::write!(formatter, "a variant name")
+                                }
+                                fn visit_str<E: #serde::de::Error>(
+                                    self, value: &str,
+                                ) -> #core::result::Result<Self::Value, E> {
+                                    match value {
+                                        #(#variant_str => #core::prelude::v1::Ok(
+                                            VariantName(#core::prelude::v1::Some(
+                                                #name::#variant_name
+                                            ))
+                                        ),)*
+                                        _ => #unknown_name,
+                                    }
+                                }
+                            }
+                            de.deserialize_str(Visitor)
+                        }
+                    }
+
+                    struct Visitor;
+                    impl <'de> #serde::de::Visitor<'de> for Visitor {
+                        type Value = #enumset::EnumSet<#name>;
+                        fn expecting(
+                            &self, formatter: &mut #core::fmt::Formatter,
+                        ) -> #core::fmt::Result {
+                            #core::write!(formatter, #expecting_str)
+                        }
+                        fn visit_seq<A>(
+                            mut self, mut seq: A,
+                        ) -> #core::result::Result<Self::Value, A::Error> where
+                            A: #serde::de::SeqAccess<'de>
+                        {
+                            let mut accum = #enumset::EnumSet::<#name>::new();
+                            while let #core::prelude::v1::Some(val) =
+                                seq.next_element::<VariantName>()?
+                            {
+                                if let #core::prelude::v1::Some(val) = val.0 {
+                                    accum |= val;
+                                }
+                            }
+                            #core::prelude::v1::Ok(accum)
+                        }
+                    }
+
+                    if de.is_human_readable() {
+                        de.deserialize_seq(Visitor)
+                    } else {
+                        #deserialize_int
+                    }
                 }
             }
         } else {
-            quote! { }
-        };
-        quote! {
-            fn serialize<S: #serde::Serializer>(
-                set: #enumset::EnumSet<#name>, ser: S,
-            ) -> #core::result::Result<S::Ok, S::Error> {
-                #serde::Serialize::serialize(&(set.__enumset_underlying as #serialize_repr), ser)
-            }
-            fn deserialize<'de, D: #serde::Deserializer<'de>>(
-                de: D,
-            ) -> #core::result::Result<#enumset::EnumSet<#name>, D::Error> {
-                let value = <#serialize_repr as #serde::Deserialize>::deserialize(de)?;
-                #check_unknown
-                #core::prelude::v1::Ok(#enumset::EnumSet {
-                    __enumset_underlying: (value & #all_variants) as #repr,
-                })
+            quote! {
+                fn serialize<S: #serde::Serializer>(
+                    set: #enumset::EnumSet<#name>, ser: S,
+                ) -> #core::result::Result<S::Ok, S::Error> {
+                    #serialize_int
+                }
+                fn deserialize<'de, D: #serde::Deserializer<'de>>(
+                    de: D,
+                ) -> #core::result::Result<#enumset::EnumSet<#name>, D::Error> {
+                    #deserialize_int
+                }
             }
         }
     };
@@ -372,10 +831,26 @@ fn enum_set_type_impl(info: EnumSetInfo) -> SynTokenStream {
     let into_impl = if is_uninhabited {
         quote! {
             fn enum_into_u32(self) -> u32 {
-                panic!(concat!(stringify!(#name), " is uninhabited."))
+                #core::panic!(#core::concat!(#core::stringify!(#name), " is uninhabited."))
+            }
+            unsafe fn enum_from_u32(val: u32) -> Self {
+                #core::panic!(#core::concat!(#core::stringify!(#name), " is uninhabited."))
+            }
+        }
+    } else if info.discriminant_is_mask {
+        let variant_name: Vec<_> = info.variants.iter().map(|x| &x.name).collect();
+        let variant_value: Vec<_> = info.variants.iter().map(|x| x.variant_repr).collect();
+        quote! {
+            fn enum_into_u32(self) -> u32 {
+                match self {
+                    #(#name::#variant_name => #variant_value,)*
+                }
             }
             unsafe fn enum_from_u32(val: u32) -> Self {
-                panic!(concat!(stringify!(#name), " is uninhabited."))
+                match val {
+                    #(#variant_value => #name::#variant_name,)*
+                    _ => #core::hint::unreachable_unchecked(),
+                }
             }
         }
     } else if is_zst {
@@ -426,7 +901,12 @@ fn enum_set_type_impl(info: EnumSetInfo) -> SynTokenStream {
     };
 
     let eq_impl = if is_uninhabited {
-        quote!(panic!(concat!(stringify!(#name), " is uninhabited.")))
+        quote!(#core::panic!(#core::concat!(#core::stringify!(#name), " is uninhabited.")))
+    } else if info.discriminant_is_mask {
+        quote! {
+            #enumset::__internal::EnumSetTypePrivate::enum_into_u32(*self) ==
+                #enumset::__internal::EnumSetTypePrivate::enum_into_u32(*other)
+        }
     } else {
         quote!((*self as u32) == (*other as u32))
     };
@@ -435,6 +915,13 @@ fn enum_set_type_impl(info: EnumSetInfo) -> SynTokenStream {
         unsafe impl #enumset::__internal::EnumSetTypePrivate for #name {
             type Repr = #repr;
             const ALL_BITS: Self::Repr = #all_variants;
+            const BIT_WIDTH: u32 = #bit_width;
+            const VARIANT_COUNT: u32 = #variant_count;
+            const DISCRIMINANT_IS_MASK: bool = #discriminant_is_mask;
+            type MapArray<V> = [#core::option::Option<V>; #map_len];
+            fn new_map_array<V>() -> Self::MapArray<V> {
+                #core::array::from_fn(|_| #core::option::Option::None)
+            }
             #into_impl
             #serde_ops
         }
@@ -455,6 +942,8 @@ fn enum_set_type_impl(info: EnumSetInfo) -> SynTokenStream {
         impl #core::marker::Copy for #name { }
 
         #ops
+        #name_impl
+        #mask_impl
     }
 }
 